@@ -355,6 +355,19 @@ impl DataType {
         matches!(self, DataType::Int16 | DataType::Int32 | DataType::Int64)
     }
 
+    /// Whether values of this type have a total order, so an aggregate like `percentile_disc`
+    /// that sorts its input can be built over it. `jsonb` has no total order; a struct is
+    /// orderable only if every one of its fields is, recursively, and likewise for a list's
+    /// element type.
+    pub fn is_orderable(&self) -> bool {
+        match self {
+            DataType::Jsonb => false,
+            DataType::Struct(t) => t.types().all(DataType::is_orderable),
+            DataType::List(t) => t.is_orderable(),
+            _ => true,
+        }
+    }
+
     /// Returns the output type of window function on a given input type.
     pub fn window_of(input: &DataType) -> Option<DataType> {
         match input {
@@ -591,6 +591,46 @@ mod tests {
         assert_eq!(floats, decoded_floats);
     }
 
+    #[test]
+    fn test_descending_ordered_float_memcomparable() {
+        use num_traits::*;
+
+        fn serialize_desc(f: F64) -> MemcmpEncoded {
+            encode_value(&Some(ScalarImpl::from(f)), OrderType::descending()).unwrap()
+        }
+
+        fn deserialize_desc(data: MemcmpEncoded) -> F64 {
+            decode_value(&DataType::Float64, &data, OrderType::descending())
+                .unwrap()
+                .unwrap()
+                .into_float64()
+        }
+
+        // Ascending order (the sign-bit-flipping monotonic byte mapping every float goes through
+        // regardless of order type, see `test_issue_legacy_2057_ordered_float_memcomparable`).
+        let floats = vec![
+            F64::neg_infinity(),
+            F64::one().neg(),
+            F64::zero(),
+            F64::one(),
+            F64::infinity(),
+        ];
+        assert!(floats.is_sorted());
+
+        // `OrderType::descending()` bit-complements that same monotonic mapping (see
+        // `memcmp_encoding::serialize_datum`'s `serializer.set_reverse`), so the encoded bytes
+        // come out in exactly the reverse of ascending order -- not, say, still ascending because
+        // some float-specific special case was missed.
+        let memcomparables = floats.clone().into_iter().map(serialize_desc).collect_vec();
+        let mut expected = memcomparables.clone();
+        expected.sort_unstable();
+        expected.reverse();
+        assert_eq!(memcomparables, expected);
+
+        let decoded_floats = memcomparables.into_iter().map(deserialize_desc).collect_vec();
+        assert_eq!(floats, decoded_floats);
+    }
+
     #[test]
     fn test_encode_row() {
         let v10 = Some(ScalarImpl::Int32(42));
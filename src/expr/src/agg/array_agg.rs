@@ -17,6 +17,13 @@ use risingwave_common::estimate_size::EstimateSize;
 use risingwave_common::types::{Datum, ScalarImpl, ScalarRef};
 use risingwave_expr_macro::aggregate;
 
+/// Collects every input datum, `NULL`s included, into a [`ListValue`] in the order rows are fed to
+/// [`array_agg`]. There's no `column_orders`-reading of its own here: per [`super::build`]'s NOTE,
+/// this crate never sorts by `column_orders` inside an aggregator -- the calling batch or streaming
+/// executor is responsible for sorting a group's rows before calling `update`/`update_batch` when
+/// the query has a `WITHIN GROUP (ORDER BY ...)` clause. Because `State` is a plain append-only
+/// `Vec`, an `array_agg` fed already-ordered input naturally emits that same order, exactly the way
+/// `WITHIN GROUP` is expected to compose with this aggregator.
 #[aggregate("array_agg(*) -> list", state = "State")]
 fn array_agg<'a, T: ScalarRef<'a>>(state: Option<State>, value: Option<T>) -> State {
     let mut state = state.unwrap_or_default();
@@ -30,6 +37,11 @@ struct State(Vec<Datum>);
 impl EstimateSize for State {
     fn estimated_heap_size(&self) -> usize {
         std::mem::size_of::<Datum>() * self.0.capacity()
+            + self
+                .0
+                .iter()
+                .map(|datum| datum.estimated_heap_size())
+                .sum::<usize>()
     }
 }
 
@@ -79,6 +91,29 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_array_agg_preserves_input_row_order() -> Result<()> {
+        // `array_agg` never sorts by itself (see the doc comment on `array_agg`); a `WITHIN GROUP
+        // (ORDER BY ...)` clause is implemented by the calling executor pre-sorting a group's rows
+        // before `update`, so this asserts the append-only `State` faithfully reproduces whatever
+        // order it's handed -- including a non-ascending one, which no sort inside `array_agg`
+        // would preserve.
+        let chunk = StreamChunk::from_pretty(
+            " i
+            + 789
+            + 123
+            + 456",
+        );
+        let mut agg = crate::agg::build(&AggCall::from_pretty("(array_agg:int4[] $0:int4)"))?;
+        agg.update(&chunk).await?;
+        let actual = agg.output()?;
+        assert_eq!(
+            actual,
+            Some(ListValue::new(vec![Some(789.into()), Some(123.into()), Some(456.into())]).into())
+        );
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_array_agg_empty() -> Result<()> {
         let mut agg = crate::agg::build(&AggCall::from_pretty("(array_agg:int4[] $0:int4)"))?;
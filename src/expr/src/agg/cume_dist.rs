@@ -0,0 +1,139 @@
+// Copyright 2023 RisingWave Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::ops::Range;
+
+use risingwave_common::array::*;
+use risingwave_common::estimate_size::EstimateSize;
+use risingwave_common::row::Row;
+use risingwave_common::types::ordered::DefaultOrdered;
+use risingwave_common::types::*;
+
+use super::Aggregator;
+use crate::Result;
+
+/// Postgres-style hypothetical-set `cume_dist`, the sibling of [`super::percent_rank::PercentRank`]
+/// sharing the same embedder-only status and construction pattern -- see that type's doc comment
+/// for why neither is wired through `#[build_aggregate(...)]`/`AggCall` yet.
+///
+/// `cume_dist(x) = (1 + count of buffered values <= x) / total_rows`, i.e. the hypothetical value's
+/// own cumulative distribution as if it had been inserted into the ordered set (unlike
+/// `percent_rank`, `total_rows` here counts the hypothetical row itself, matching Postgres).
+#[derive(Clone, EstimateSize)]
+pub struct CumeDist {
+    return_type: DataType,
+    hypothetical: ScalarImpl,
+    data: Vec<ScalarImpl>,
+}
+
+impl CumeDist {
+    pub fn new(hypothetical: ScalarImpl, return_type: DataType) -> Self {
+        Self {
+            return_type,
+            hypothetical,
+            data: vec![],
+        }
+    }
+
+    fn add_datum(&mut self, datum_ref: DatumRef<'_>) {
+        if let Some(scalar) = datum_ref.to_owned_datum() {
+            self.data.push(scalar);
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Aggregator for CumeDist {
+    fn return_type(&self) -> DataType {
+        self.return_type.clone()
+    }
+
+    async fn update(&mut self, input: &StreamChunk) -> Result<()> {
+        for (_, row) in input.rows() {
+            self.add_datum(row.datum_at(0));
+        }
+        Ok(())
+    }
+
+    async fn update_range(&mut self, input: &StreamChunk, range: Range<usize>) -> Result<()> {
+        for (_, row) in input.rows_in(range) {
+            self.add_datum(row.datum_at(0));
+        }
+        Ok(())
+    }
+
+    fn get_output(&self) -> Result<Datum> {
+        if self.data.is_empty() {
+            return Ok(Some(ScalarImpl::from(1.0)));
+        }
+        let at_most = self
+            .data
+            .iter()
+            .filter(|v| DefaultOrdered((*v).clone()) <= DefaultOrdered(self.hypothetical.clone()))
+            .count();
+        // `+ 1` accounts for the hypothetical row itself, which is never in `self.data`.
+        let cume_dist = (at_most + 1) as f64 / (self.data.len() + 1) as f64;
+        Ok(Some(ScalarImpl::from(cume_dist)))
+    }
+
+    fn output(&mut self) -> Result<Datum> {
+        let result = self.get_output()?;
+        self.reset();
+        Ok(result)
+    }
+
+    fn reset(&mut self) {
+        self.data.clear();
+    }
+
+    fn get_state(&self) -> Datum {
+        unimplemented!("get_state is not supported for cume_dist");
+    }
+
+    fn set_state(&mut self, _: Datum) {
+        unimplemented!("set_state is not supported for cume_dist");
+    }
+
+    fn estimated_size(&self) -> usize {
+        EstimateSize::estimated_size(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cume_dist_basic() -> Result<()> {
+        let chunk = StreamChunk::from_pretty(
+            " i
+            + 1
+            + 2
+            + 3
+            + 4",
+        );
+        let mut agg = CumeDist::new(ScalarImpl::from(3), DataType::Float64);
+        agg.update(&chunk).await?;
+        // Three values (1, 2, 3) are <= 3; (3 + 1) / (4 + 1) = 0.8
+        assert_eq!(agg.output()?, Some(ScalarImpl::from(0.8)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cume_dist_empty_is_one() -> Result<()> {
+        let mut agg = CumeDist::new(ScalarImpl::from(1), DataType::Float64);
+        assert_eq!(agg.output()?, Some(ScalarImpl::from(1.0)));
+        Ok(())
+    }
+}
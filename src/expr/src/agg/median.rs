@@ -0,0 +1,250 @@
+// Copyright 2023 RisingWave Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::ops::Range;
+
+use risingwave_common::array::*;
+use risingwave_common::estimate_size::EstimateSize;
+use risingwave_common::row::Row;
+use risingwave_common::types::*;
+use risingwave_expr_macro::build_aggregate;
+
+use super::percentile_disc::{classify_ordered_set_input, f64_to_scalar, sort_by_default_order, OrderedSetInput};
+use super::Aggregator;
+use crate::agg::AggCall;
+use crate::Result;
+
+/// Convenience spelling of `percentile_disc(0.5)`/`percentile_cont(0.5)` without the verbose
+/// `WITHIN GROUP` fraction. Reuses [`super::percentile_disc::sort_by_default_order`] for the
+/// actual sort-then-index-by-rank work rather than duplicating it -- the same helper
+/// [`super::percentile_disc::multi_percentile_disc`] is built on.
+///
+/// For an odd count, or a `return_type` other than the integer and floating-point types, this
+/// returns the lower-middle element verbatim -- exactly `percentile_disc(0.5)`'s discrete
+/// behavior. For an even count of an integer or floating-point `return_type`, it averages the two
+/// middle elements as `f64` and converts back, matching `percentile_cont(0.5)`'s continuous
+/// behavior for that one case; every other type, `decimal` and `serial` included, has no `f64`
+/// conversion here and keeps the discrete lower-middle answer regardless of parity.
+#[build_aggregate("median(*) -> auto")]
+fn build(agg: &AggCall) -> Result<Box<dyn Aggregator>> {
+    if !agg.return_type.is_orderable() {
+        return Err(crate::ExprError::InvalidParam {
+            name: "expr",
+            reason: format!(
+                "median requires a type with a total order, but {} has none",
+                agg.return_type
+            )
+            .into(),
+        });
+    }
+    Ok(Box::new(Median::new(agg.return_type.clone())))
+}
+
+#[derive(Clone, EstimateSize)]
+pub struct Median {
+    return_type: DataType,
+    data: Vec<ScalarImpl>,
+    /// `IGNORE NULLS` (the default) drops a `NULL` input as though it was never aggregated;
+    /// `RESPECT NULLS` counts it in [`Self::null_count`] instead, so it occupies a trailing
+    /// (`NULLS LAST`) position when [`Self::get_output`] computes the middle rank(s). See
+    /// [`Self::with_ignore_nulls`] and [`super::percentile_disc::PercentileDisc::ignore_nulls`],
+    /// which this mirrors via the shared [`classify_ordered_set_input`].
+    ignore_nulls: bool,
+    /// Count of NULL inputs buffered so far when `ignore_nulls` is `false`.
+    null_count: usize,
+}
+
+impl Median {
+    pub fn new(return_type: DataType) -> Self {
+        Self {
+            return_type,
+            data: vec![],
+            ignore_nulls: true,
+            null_count: 0,
+        }
+    }
+
+    /// Like [`Self::new`], but with an explicit `RESPECT NULLS` (`ignore_nulls: false`) vs
+    /// `IGNORE NULLS` (`ignore_nulls: true`, the default) choice.
+    pub fn with_ignore_nulls(return_type: DataType, ignore_nulls: bool) -> Self {
+        Self {
+            ignore_nulls,
+            ..Self::new(return_type)
+        }
+    }
+
+    fn add_datum(&mut self, datum_ref: DatumRef<'_>) {
+        match classify_ordered_set_input(datum_ref, self.ignore_nulls) {
+            OrderedSetInput::Value(scalar) => self.data.push(scalar),
+            OrderedSetInput::RespectedNull => self.null_count += 1,
+            OrderedSetInput::IgnoredNull => {}
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Aggregator for Median {
+    fn return_type(&self) -> DataType {
+        self.return_type.clone()
+    }
+
+    async fn update(&mut self, input: &StreamChunk) -> Result<()> {
+        for (_, row) in input.rows() {
+            self.add_datum(row.datum_at(0));
+        }
+        Ok(())
+    }
+
+    async fn update_range(&mut self, input: &StreamChunk, range: Range<usize>) -> Result<()> {
+        for (_, row) in input.rows_in(range) {
+            self.add_datum(row.datum_at(0));
+        }
+        Ok(())
+    }
+
+    fn get_output(&self) -> Result<Datum> {
+        if self.data.is_empty() {
+            return Ok(None);
+        }
+        // Under `RESPECT NULLS`, buffered NULLs sort last, so the middle rank(s) are computed
+        // over `ordered_len` (non-NULL values plus counted NULLs) rather than just `data.len()`.
+        let ordered_len = self.data.len() + self.null_count;
+        let lower_idx = (ordered_len - 1) / 2;
+        let upper_idx = ordered_len / 2;
+        if upper_idx >= self.data.len() {
+            // The middle rank(s) land on or span the trailing NULLs.
+            return Ok(None);
+        }
+        let sorted = sort_by_default_order(&self.data);
+        if upper_idx == lower_idx || !self.return_type.is_numeric() {
+            return Ok(Some(sorted[lower_idx].clone()));
+        }
+        let lower = scalar_to_f64(&sorted[lower_idx]);
+        let upper = scalar_to_f64(&sorted[upper_idx]);
+        let (Some(lower), Some(upper)) = (lower, upper) else {
+            return Ok(Some(sorted[lower_idx].clone()));
+        };
+        Ok(f64_to_scalar((lower + upper) / 2.0, &self.return_type))
+    }
+
+    fn output(&mut self) -> Result<Datum> {
+        let result = self.get_output()?;
+        self.reset();
+        Ok(result)
+    }
+
+    fn reset(&mut self) {
+        self.data.clear();
+        self.null_count = 0;
+    }
+
+    fn get_state(&self) -> Datum {
+        unimplemented!("get_state is not supported for median");
+    }
+
+    fn set_state(&mut self, _: Datum) {
+        unimplemented!("set_state is not supported for median");
+    }
+
+    fn estimated_size(&self) -> usize {
+        EstimateSize::estimated_size(self)
+    }
+}
+
+/// The reverse of [`f64_to_scalar`], for the same enumerated set of plain numeric types -- the
+/// only ones [`Median::get_output`] ever averages two middle elements of.
+fn scalar_to_f64(value: &ScalarImpl) -> Option<f64> {
+    match value {
+        ScalarImpl::Int16(v) => Some(*v as f64),
+        ScalarImpl::Int32(v) => Some(*v as f64),
+        ScalarImpl::Int64(v) => Some(*v as f64),
+        ScalarImpl::Float32(v) => Some((*v).into()),
+        ScalarImpl::Float64(v) => Some((*v).into()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_median_odd_count_returns_middle_element() -> Result<()> {
+        let chunk = StreamChunk::from_pretty(
+            " i
+            + 5
+            + 1
+            + 3",
+        );
+        let mut agg = Median::new(DataType::Int32);
+        agg.update(&chunk).await?;
+        assert_eq!(agg.output()?, Some(ScalarImpl::from(3)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_ignore_nulls_default_drops_null_and_respect_nulls_counts_it() -> Result<()> {
+        let chunk = StreamChunk::from_pretty(
+            " i
+            + 5
+            + 1
+            + 3
+            + .",
+        );
+
+        // `IGNORE NULLS` (the default): the NULL is dropped entirely, so this is just the median
+        // of `1, 3, 5`.
+        let mut agg = Median::new(DataType::Int32);
+        agg.update(&chunk).await?;
+        assert_eq!(agg.output()?, Some(ScalarImpl::from(3)));
+
+        // Flipping to `RESPECT NULLS` counts the NULL as a trailing (`NULLS LAST`) fourth value,
+        // so the middle two of `1, 3, 5, NULL` are now `3` and `5`, averaged to `4`.
+        let mut agg = Median::with_ignore_nulls(DataType::Int32, false);
+        agg.update(&chunk).await?;
+        assert_eq!(agg.output()?, Some(ScalarImpl::from(4)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_median_even_count_averages_middle_elements() -> Result<()> {
+        let chunk = StreamChunk::from_pretty(
+            " i
+            + 1
+            + 2
+            + 3
+            + 4",
+        );
+        let mut agg = Median::new(DataType::Int32);
+        agg.update(&chunk).await?;
+        // Middle two values are 2 and 3; averaged as f64 and rounded back to Int32.
+        assert_eq!(agg.output()?, Some(ScalarImpl::from(3)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_median_even_count_non_numeric_returns_lower_middle() -> Result<()> {
+        let chunk = StreamChunk::from_pretty(
+            " T
+            + ccc
+            + aaa
+            + ddd
+            + bbb",
+        );
+        let mut agg = Median::new(DataType::Varchar);
+        agg.update(&chunk).await?;
+        assert_eq!(agg.output()?, Some(ScalarImpl::from("bbb")));
+        Ok(())
+    }
+}
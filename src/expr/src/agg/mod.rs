@@ -15,7 +15,7 @@
 use std::ops::Range;
 
 use dyn_clone::DynClone;
-use risingwave_common::array::StreamChunk;
+use risingwave_common::array::{DataChunk, StreamChunk};
 use risingwave_common::estimate_size::EstimateSize;
 use risingwave_common::types::{DataType, DataTypeName, Datum};
 
@@ -28,9 +28,12 @@ mod def;
 // concrete aggregators
 mod approx_count_distinct;
 mod array_agg;
+mod cume_dist;
 mod general;
 mod jsonb_agg;
+mod median;
 mod mode;
+mod percent_rank;
 mod percentile_cont;
 mod percentile_disc;
 mod string_agg;
@@ -48,6 +51,23 @@ pub trait Aggregator: Send + Sync + DynClone + 'static {
     /// Update the aggregator with a range of rows.
     async fn update_range(&mut self, input: &StreamChunk, range: Range<usize>) -> Result<()>;
 
+    /// Update the aggregator with a batch-engine [`DataChunk`], which (unlike a [`StreamChunk`])
+    /// carries no op column since every row is logically an insert. The default wraps `input`
+    /// into an all-[`Op::Insert`](risingwave_common::array::Op::Insert) [`StreamChunk`] and
+    /// defers to [`Self::update`]; an aggregator that buffers its input directly (rather than
+    /// folding it into running state incrementally) can override this to skip synthesizing the
+    /// op column.
+    ///
+    /// Not currently called by either batch aggregate executor: `HashAggExecutor` and
+    /// `SortAggExecutor` both convert their child's `DataChunk` to a `StreamChunk` themselves and
+    /// call [`Self::update_range`] directly, since they need to dispatch each row (or sub-range)
+    /// to a specific group's state rather than feed a whole chunk to one aggregator. Wiring either
+    /// executor's whole-chunk-single-group fast path through this method instead is real,
+    /// separate work that hasn't been done.
+    async fn update_batch(&mut self, input: &DataChunk) -> Result<()> {
+        self.update(&input.clone().into()).await
+    }
+
     /// Get the output value.
     fn get_output(&self) -> Result<Datum>;
 
@@ -65,6 +85,13 @@ pub trait Aggregator: Send + Sync + DynClone + 'static {
 
     /// The estimated size of the state.
     fn estimated_size(&self) -> usize;
+
+    /// The number of rows accumulated so far, for the optimizer to refine cardinality estimates.
+    /// `None` by default; aggregators that can report this cheaply (e.g. those that already keep
+    /// every input buffered) should override it.
+    fn accumulated_count(&self) -> Option<usize> {
+        None
+    }
 }
 
 dyn_clone::clone_trait_object!(Aggregator);
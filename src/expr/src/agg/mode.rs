@@ -33,6 +33,11 @@ fn build(agg: &AggCall) -> Result<Box<dyn Aggregator>> {
 /// first one if there are multiple equally-frequent values). The aggregated argument must be of a
 /// sortable type.
 ///
+/// Follows the same per-row `add_datum` structure as [`super::percentile_disc::PercentileDisc`]
+/// and [`super::percentile_cont::PercentileCont`] without sharing code with either, and buffers
+/// nothing: unlike a percentile, the mode never needs the full set sorted or spilled, so it tracks only the
+/// current run's value and count as rows arrive in `WITHIN GROUP` order.
+///
 /// ```slt
 /// query I
 /// select mode() within group (order by unnest) from unnest(array[1]);
@@ -0,0 +1,147 @@
+// Copyright 2023 RisingWave Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::ops::Range;
+
+use risingwave_common::array::*;
+use risingwave_common::estimate_size::EstimateSize;
+use risingwave_common::row::Row;
+use risingwave_common::types::ordered::DefaultOrdered;
+use risingwave_common::types::*;
+
+use super::Aggregator;
+use crate::Result;
+
+/// Postgres-style hypothetical-set `percent_rank`/`cume_dist`, computed against a hypothetical
+/// row inserted into the `WITHIN GROUP`-ordered set rather than against an already-aggregated
+/// column value the way [`super::percentile_disc::PercentileDisc`] and
+/// [`super::percentile_cont::PercentileCont`] are. Standard SQL spells this
+/// `percent_rank(hypothetical_value) within group (order by column)`; there is no grammar or
+/// planner support for hypothetical-set aggregates in this repo yet, so unlike those two,
+/// [`PercentRank`] isn't reachable via `#[build_aggregate(...)]`/`AggCall` -- it's an
+/// embedder-only type for now, constructed directly with [`PercentRank::new`].
+///
+/// `percent_rank(x) = (rank - 1) / (total_rows - 1)`, where `rank` is one more than the count of
+/// buffered values strictly less than `x` (0 when `total_rows <= 1`, matching Postgres).
+#[derive(Clone, EstimateSize)]
+pub struct PercentRank {
+    return_type: DataType,
+    hypothetical: ScalarImpl,
+    data: Vec<ScalarImpl>,
+}
+
+impl PercentRank {
+    pub fn new(hypothetical: ScalarImpl, return_type: DataType) -> Self {
+        Self {
+            return_type,
+            hypothetical,
+            data: vec![],
+        }
+    }
+
+    fn add_datum(&mut self, datum_ref: DatumRef<'_>) {
+        if let Some(scalar) = datum_ref.to_owned_datum() {
+            self.data.push(scalar);
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Aggregator for PercentRank {
+    fn return_type(&self) -> DataType {
+        self.return_type.clone()
+    }
+
+    async fn update(&mut self, input: &StreamChunk) -> Result<()> {
+        for (_, row) in input.rows() {
+            self.add_datum(row.datum_at(0));
+        }
+        Ok(())
+    }
+
+    async fn update_range(&mut self, input: &StreamChunk, range: Range<usize>) -> Result<()> {
+        for (_, row) in input.rows_in(range) {
+            self.add_datum(row.datum_at(0));
+        }
+        Ok(())
+    }
+
+    fn get_output(&self) -> Result<Datum> {
+        if self.data.len() <= 1 {
+            return Ok(Some(ScalarImpl::from(0.0)));
+        }
+        let less_than = self
+            .data
+            .iter()
+            .filter(|v| DefaultOrdered((*v).clone()) < DefaultOrdered(self.hypothetical.clone()))
+            .count();
+        let percent_rank = less_than as f64 / (self.data.len() - 1) as f64;
+        Ok(Some(ScalarImpl::from(percent_rank)))
+    }
+
+    fn output(&mut self) -> Result<Datum> {
+        let result = self.get_output()?;
+        self.reset();
+        Ok(result)
+    }
+
+    fn reset(&mut self) {
+        self.data.clear();
+    }
+
+    fn get_state(&self) -> Datum {
+        unimplemented!("get_state is not supported for percent_rank");
+    }
+
+    fn set_state(&mut self, _: Datum) {
+        unimplemented!("set_state is not supported for percent_rank");
+    }
+
+    fn estimated_size(&self) -> usize {
+        EstimateSize::estimated_size(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_percent_rank_basic() -> Result<()> {
+        let chunk = StreamChunk::from_pretty(
+            " i
+            + 1
+            + 2
+            + 3
+            + 4",
+        );
+        let mut agg = PercentRank::new(ScalarImpl::from(3), DataType::Float64);
+        agg.update(&chunk).await?;
+        // Two values (1, 2) are strictly less than 3 out of 4 rows: (2) / (4 - 1) = 0.666...
+        assert_eq!(agg.output()?, Some(ScalarImpl::from(2.0 / 3.0)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_percent_rank_single_row_is_zero() -> Result<()> {
+        let chunk = StreamChunk::from_pretty(
+            " i
+            + 1",
+        );
+        let mut agg = PercentRank::new(ScalarImpl::from(1), DataType::Float64);
+        agg.update(&chunk).await?;
+        assert_eq!(agg.output()?, Some(ScalarImpl::from(0.0)));
+        Ok(())
+    }
+}
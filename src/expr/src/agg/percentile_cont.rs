@@ -28,6 +28,10 @@ use crate::Result;
 /// ordered set of aggregated argument values. This will interpolate between adjacent input items if
 /// needed.
 ///
+/// The continuous-interpolation counterpart to [`super::percentile_disc::PercentileDisc`], which
+/// always returns one of the aggregated values verbatim rather than interpolating between two
+/// adjacent ones.
+///
 /// ```slt
 /// statement ok
 /// create table t(x int, y bigint, z real, w double, v varchar);
@@ -12,11 +12,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::cell::{Cell, RefCell};
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::hash::{Hash, Hasher};
 use std::ops::Range;
+use std::sync::Arc;
 
 use risingwave_common::array::*;
 use risingwave_common::estimate_size::EstimateSize;
 use risingwave_common::row::Row;
+use risingwave_common::types::ordered::DefaultOrdered;
 use risingwave_common::types::*;
 use risingwave_expr_macro::build_aggregate;
 
@@ -24,9 +30,15 @@ use super::Aggregator;
 use crate::agg::AggCall;
 use crate::Result;
 
+/// Above this many buffered values, [`PercentileDisc`] sorts and spills the current run instead
+/// of keeping growing an unsorted buffer, so a single group with huge cardinality doesn't hold
+/// all of its values unsorted in memory at once.
+const DEFAULT_SPILL_THRESHOLD: usize = 1 << 16;
+
 /// Computes the discrete percentile, the first value within the ordered set of aggregated argument
 /// values whose position in the ordering equals or exceeds the specified fraction. The aggregated
-/// argument must be of a sortable type.
+/// argument must be of a sortable type, including `time`; only the fraction and rank arithmetic
+/// go through `f64`, the aggregated values themselves are never converted.
 ///
 /// ```slt
 /// statement ok
@@ -60,90 +72,1517 @@ use crate::Result;
 /// ----
 /// 30000
 ///
-/// query R
+/// query error percentile_disc requires a non-NULL constant fraction
 /// select percentile_disc(NULL) within group (order by w) from t;
-/// ----
-/// NULL
 ///
 /// statement ok
 /// drop table t;
+///
+/// statement ok
+/// create table s(t time);
+///
+/// statement ok
+/// insert into s values('09:00:00'), ('12:00:00'), ('15:00:00');
+///
+/// query R
+/// select percentile_disc(0.5) within group (order by t) from s;
+/// ----
+/// 12:00:00
+///
+/// statement ok
+/// drop table s;
+///
+/// statement ok
+/// create table u(w varchar);
+///
+/// statement ok
+/// insert into u values('ccc'), ('aaa'), ('bbb');
+///
+/// query T
+/// select percentile_disc(0.5) within group (order by w) from u;
+/// ----
+/// bbb
+///
+/// statement ok
+/// drop table u;
 /// ```
 #[build_aggregate("percentile_disc(*) -> auto")]
 fn build(agg: &AggCall) -> Result<Box<dyn Aggregator>> {
-    let fraction: Option<f64> = agg.direct_args[0]
-        .literal()
-        .map(|x| (*x.as_float64()).into());
+    let Some(direct_arg) = agg.direct_args.first() else {
+        return Err(crate::ExprError::InvalidParam {
+            name: "fraction",
+            reason: "percentile_disc requires exactly one direct argument (the fraction), got none"
+                .into(),
+        });
+    };
+    if !agg.return_type.is_orderable() {
+        return Err(crate::ExprError::InvalidParam {
+            name: "expr",
+            reason: format!(
+                "percentile_disc requires a type with a total order, but {} has none",
+                agg.return_type
+            )
+            .into(),
+        });
+    }
+    // `percentile_disc` always returns one of its buffered inputs verbatim (see this function's
+    // doc comment), so a `return_type` that doesn't match the `ORDER BY` argument's type would
+    // let a mismatched `deserialize`/`as_*` conversion reach `PercentileDisc` and panic well past
+    // where the mistake was actually made. Reject it here instead, at `build` time.
+    if let Some(arg_type) = agg.args.arg_types().first() {
+        if arg_type != &agg.return_type {
+            return Err(crate::ExprError::InvalidParam {
+                name: "expr",
+                reason: format!(
+                    "percentile_disc's return type must match its ordered argument's type, but \
+                     the argument is {} and the return type is {}",
+                    arg_type, agg.return_type
+                )
+                .into(),
+            });
+        }
+    }
+    // `direct_arg` is a `LiteralExpression` (see `AggCall::direct_args`'s doc comment on
+    // `PercentileDisc::new_with_deferred_fraction`): the frontend binder has already reduced
+    // whatever expression the user wrote to a constant literal by the time `build` runs, so
+    // there's no non-literal expression to evaluate here. The one way `.literal()` can still fail
+    // us is a literal that is itself NULL (e.g. `percentile_disc(NULL)`); silently treating that
+    // as "fraction unknown" would degrade to a `NULL` result instead of surfacing the mistake, so
+    // reject it up front instead.
+    let Some(literal) = direct_arg.literal() else {
+        return Err(crate::ExprError::InvalidParam {
+            name: "fraction",
+            reason: "percentile_disc requires a non-NULL constant fraction".into(),
+        });
+    };
+    let fraction = fraction_from_literal(&literal)?;
     Ok(Box::new(PercentileDisc::new(
-        fraction,
+        Some(fraction),
         agg.return_type.clone(),
     )))
 }
 
+/// Reads `literal` as the `fraction` direct argument, accepting both an integer literal (e.g. a
+/// user writing `percentile_disc(1)` rather than `1.0`) and a floating-point one, coercing an
+/// integer to `f64` rather than requiring the caller to have written a float literal.
+fn fraction_from_literal(literal: &ScalarImpl) -> Result<f64> {
+    let fraction = match literal {
+        ScalarImpl::Int16(v) => *v as f64,
+        ScalarImpl::Int32(v) => *v as f64,
+        ScalarImpl::Int64(v) => *v as f64,
+        ScalarImpl::Float32(v) => (*v).0 as f64,
+        ScalarImpl::Float64(v) => (*v).0,
+        other => {
+            return Err(crate::ExprError::InvalidParam {
+                name: "fraction",
+                reason: format!(
+                    "percentile_disc fraction must be an integer or floating-point literal, got {}",
+                    other.get_ident()
+                )
+                .into(),
+            })
+        }
+    };
+    // `RoundingPolicy::rank` clamps its own result to `[0, total_len - 1]`, so an out-of-range
+    // fraction wouldn't panic there -- but it would still silently produce a nonsensical rank
+    // (e.g. `1.5` ranking the same as `1.0`) instead of surfacing the mistake. Reject it here,
+    // at `build` time, rather than let a bad direct argument reach aggregation at all.
+    if !(0.0..=1.0).contains(&fraction) {
+        return Err(crate::ExprError::InvalidParam {
+            name: "fraction",
+            reason: format!(
+                "percentile_disc fraction must be between 0 and 1, got {fraction}"
+            )
+            .into(),
+        });
+    }
+    Ok(fraction)
+}
+
+/// How a fractional rank (`fraction * n`) is mapped to a 0-indexed position in the sorted data.
+/// `Ceil` is the standard nearest-rank-up behavior (matching Postgres's `percentile_disc`); the
+/// other two are opt-in for parity with tools that pick a different element at the boundary.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RoundingPolicy {
+    #[default]
+    Ceil,
+    Round,
+    Floor,
+}
+
+impl RoundingPolicy {
+    /// Maps `fraction` over `total_len` buffered values to a 0-indexed rank.
+    ///
+    /// The result is clamped to `[0, total_len - 1]`: in exact arithmetic `fraction * total_len`
+    /// never exceeds `total_len`, but floating-point rounding can push it a hair over (e.g.
+    /// `fraction` arriving as the next `f64` above an exact `1.0`), which without
+    /// clamping would index one past the end of the sorted data.
+    fn rank(self, fraction: f64, total_len: usize) -> usize {
+        if fraction == 0.0 || total_len == 0 {
+            return 0;
+        }
+        let rn = fraction * total_len as f64;
+        let rank = match self {
+            RoundingPolicy::Ceil => f64::ceil(rn) as usize,
+            RoundingPolicy::Round => f64::round(rn) as usize,
+            RoundingPolicy::Floor => f64::floor(rn) as usize,
+        };
+        rank.clamp(1, total_len) - 1
+    }
+}
+
+/// Above this many centroids, [`TDigest::insert`] merges the two closest ones to keep the digest
+/// bounded regardless of how much data flows through it.
+const DIGEST_MAX_CENTROIDS: usize = 256;
+
+/// A weighted mean, standing in for `weight` original values that have been folded together.
+#[derive(Clone, Copy, Debug)]
+struct Centroid {
+    mean: f64,
+    weight: f64,
+}
+
+/// A size-bounded approximate summary of a numeric distribution, for [`PercentileDisc`] to fall
+/// back to once [`PercentileDisc::with_memory_budget`]'s exact buffering would grow past budget.
+///
+/// This is a simplified digest, not a full scale-function t-digest: a real t-digest varies how
+/// aggressively it merges by rank (finer centroids near the tails, coarser in the middle) so its
+/// tail quantiles stay far more accurate than its median. This always merges whichever two
+/// centroids are numerically closest, so accuracy degrades more evenly — and more coarsely at the
+/// tails — as more data is folded in. That's an acceptable trade for a safety valve whose whole
+/// point is bounding memory, not matching production-grade digest accuracy.
+#[derive(Clone, Debug, Default)]
+struct TDigest {
+    /// Always kept sorted by `mean`.
+    centroids: Vec<Centroid>,
+    max_centroids: usize,
+    /// Total original values folded in, including ones now merged away. Kept separately from
+    /// summing `centroids`' weights so it stays an exact integer count.
+    count: u64,
+}
+
+impl TDigest {
+    fn new(max_centroids: usize) -> Self {
+        Self {
+            centroids: Vec::new(),
+            max_centroids,
+            count: 0,
+        }
+    }
+
+    fn insert(&mut self, value: f64) {
+        let idx = self.centroids.partition_point(|c| c.mean < value);
+        self.centroids.insert(idx, Centroid { mean: value, weight: 1.0 });
+        self.count += 1;
+        if self.centroids.len() > self.max_centroids {
+            self.compress();
+        }
+    }
+
+    /// Merges the two adjacent centroids with the smallest gap between their means, repeatedly,
+    /// until back within `max_centroids`.
+    fn compress(&mut self) {
+        while self.centroids.len() > self.max_centroids {
+            let merge_at = self
+                .centroids
+                .windows(2)
+                .enumerate()
+                .map(|(i, pair)| (i, pair[1].mean - pair[0].mean))
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .expect("more than one centroid while compressing")
+                .0;
+            let a = self.centroids[merge_at];
+            let b = self.centroids[merge_at + 1];
+            let weight = a.weight + b.weight;
+            let merged = Centroid {
+                mean: (a.mean * a.weight + b.mean * b.weight) / weight,
+                weight,
+            };
+            self.centroids.splice(merge_at..=merge_at + 1, [merged]);
+        }
+    }
+
+    fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Approximates the value at 0-indexed `rank` among all folded-in values, by walking
+    /// centroids in order until their cumulative weight passes `rank`.
+    fn quantile_at_rank(&self, rank: u64) -> Option<f64> {
+        let mut cumulative = 0.0;
+        for centroid in &self.centroids {
+            cumulative += centroid.weight;
+            if cumulative > rank as f64 {
+                return Some(centroid.mean);
+            }
+        }
+        self.centroids.last().map(|c| c.mean)
+    }
+
+    /// The widest centroid's weight as a fraction of everything folded in: the most any single
+    /// centroid's averaging could have shifted the apparent rank of a value it absorbed, and so
+    /// an upper bound on how far a quantile answer's rank can be from the requested one.
+    fn error_bound(&self) -> f64 {
+        if self.count == 0 {
+            return 0.0;
+        }
+        let widest = self.centroids.iter().map(|c| c.weight).fold(0.0, f64::max);
+        widest / self.count as f64
+    }
+}
+
+/// Converts a buffered value to `f64` for [`TDigest`] insertion. `None` for any type the digest
+/// can't approximate (only plain numeric types); [`PercentileDisc`] simply never switches over
+/// for such data, staying exact — and therefore unbounded in memory — since there's no numeric
+/// summary to fall back to for e.g. `varchar`.
+fn scalar_to_f64(value: &ScalarImpl) -> Option<f64> {
+    match value {
+        ScalarImpl::Int16(v) => Some(*v as f64),
+        ScalarImpl::Int32(v) => Some(*v as f64),
+        ScalarImpl::Int64(v) => Some(*v as f64),
+        ScalarImpl::Float32(v) => Some(v.0 as f64),
+        ScalarImpl::Float64(v) => Some(v.0),
+        _ => None,
+    }
+}
+
+/// Converts a digest-estimated value back into a [`ScalarImpl`] of `return_type`. `percentile_disc`
+/// is normally discrete (it returns one of its inputs verbatim), but a digest only keeps centroid
+/// means, not the original values, so an approximate answer is inherently continuous; this rounds
+/// back to the nearest representable value for an integer `return_type` rather than pretending
+/// the fractional part means anything.
+pub(crate) fn f64_to_scalar(value: f64, return_type: &DataType) -> Option<ScalarImpl> {
+    match return_type {
+        DataType::Int16 => Some(ScalarImpl::from(value.round() as i16)),
+        DataType::Int32 => Some(ScalarImpl::from(value.round() as i32)),
+        DataType::Int64 => Some(ScalarImpl::from(value.round() as i64)),
+        DataType::Float32 => Some(ScalarImpl::from(value as f32)),
+        DataType::Float64 => Some(ScalarImpl::from(value)),
+        _ => None,
+    }
+}
+
+/// The `IGNORE NULLS`/`RESPECT NULLS` classification of one `add_datum` input, shared by every
+/// ordered-set aggregate in this module that buffers non-NULL values into a plain
+/// `Vec<ScalarImpl>` -- see [`classify_ordered_set_input`].
+pub(crate) enum OrderedSetInput {
+    /// A non-NULL value to buffer.
+    Value(ScalarImpl),
+    /// `RESPECT NULLS` (`ignore_nulls: false`): not buffered, but should still be counted so
+    /// rank-based lookups know it occupies a trailing (`NULLS LAST`) position in sorted order.
+    RespectedNull,
+    /// `IGNORE NULLS` (`ignore_nulls: true`, the default): dropped as though it was never
+    /// aggregated.
+    IgnoredNull,
+}
+
+/// Classifies one ordered-set aggregate input against its `ignore_nulls` flag, so the choice
+/// between dropping a `NULL` entirely and counting it as a trailing `NULLS LAST` value is made in
+/// exactly one place instead of every `add_datum` implementing (and risking diverging on) its own
+/// copy. Used by [`PercentileDisc::add_datum`] and [`super::median::Median::add_datum`].
+pub(crate) fn classify_ordered_set_input(
+    datum_ref: DatumRef<'_>,
+    ignore_nulls: bool,
+) -> OrderedSetInput {
+    match datum_ref.to_owned_datum() {
+        Some(datum) => OrderedSetInput::Value(datum),
+        None if !ignore_nulls => OrderedSetInput::RespectedNull,
+        None => OrderedSetInput::IgnoredNull,
+    }
+}
+
 #[derive(Clone)]
 pub struct PercentileDisc {
     fractions: Option<f64>,
     return_type: DataType,
+    rounding: RoundingPolicy,
     data: Vec<ScalarImpl>,
+    /// Sorted runs that were spilled out of `data` because it grew past `spill_threshold`.
+    spilled_runs: Vec<Vec<ScalarImpl>>,
+    spill_threshold: usize,
+    /// Memoized `(data fingerprint, result)` from the last `get_output`, so repeated polling
+    /// (e.g. materialized-view refresh) of an unchanged group doesn't redo the selection.
+    output_cache: RefCell<Option<(u64, Datum)>>,
+    /// Number of times `get_output` actually recomputed instead of hitting `output_cache`.
+    /// Exposed for tests only.
+    recompute_count: Cell<usize>,
+    /// Whether `fractions` has been resolved, distinguishing a NULL fraction (`fractions: None`,
+    /// resolved) from a fraction still pending on a runtime parameter (see
+    /// [`Self::new_with_deferred_fraction`]).
+    fraction_resolved: bool,
+    /// `true` (the default, matching `percentile_disc`'s SQL semantics) skips NULL inputs
+    /// entirely, as if they were never aggregated. `false` keeps them in the ordered set as the
+    /// greatest elements (`NULLS LAST`), so a fraction whose rank lands among them yields NULL
+    /// instead of skipping past them to the highest non-NULL value. See [`Self::with_ignore_nulls`].
+    ignore_nulls: bool,
+    /// Count of NULL inputs buffered so far when `ignore_nulls` is `false`. Kept separate from
+    /// `data`/`spilled_runs`, which only ever hold non-NULL values, so the sort/merge machinery
+    /// doesn't need to special-case NULLs; only rank arithmetic needs to know they're there.
+    null_count: usize,
+    /// `true` when the planner guarantees rows arrive already sorted by the order-by column, so
+    /// [`Aggregator::output`]'s move-out fast path can trust arrival order and skip its sort. In
+    /// debug builds, [`Self::add_datum`] asserts each arrival is `>=` the previous one. See
+    /// [`Self::with_presorted_hint`].
+    presorted: bool,
+    /// Number of times buffered data was actually sorted (a `spill_current_run` or `output` move-
+    /// out sort). Exposed for tests only, to confirm `presorted` really elides the sort.
+    sort_count: Cell<usize>,
+    /// A locale-aware comparator overriding the default byte-order [`DefaultOrdered`] comparison,
+    /// for a `varchar` order-by column with a `COLLATE` clause. `None` (the default) compares by
+    /// byte order. See [`Self::with_comparator`] for why `build` never sets this itself.
+    comparator: Option<Arc<dyn Fn(&ScalarImpl, &ScalarImpl) -> Ordering + Send + Sync>>,
+    /// Once `estimated_heap_size` exceeds this many bytes, `add_datum` folds everything buffered
+    /// so far into `digest` and stops growing `data`/`spilled_runs` further. `None` (the default)
+    /// never switches over, keeping `PercentileDisc` exact and unbounded in memory. See
+    /// [`Self::with_memory_budget`].
+    memory_budget: Option<usize>,
+    /// The approximate summary switched to once `memory_budget` is exceeded. `None` until (or
+    /// unless) that happens.
+    digest: Option<TDigest>,
+    /// `true` once [`Self::with_tie_break`] configures a secondary order-by column: when two
+    /// buffered values tie under the primary comparison, ties are broken by comparing the
+    /// corresponding element of `secondary` instead of leaving them in arbitrary sort-stable
+    /// order. [`Self::get_output`] and [`Aggregator::output`] consult it while nothing has
+    /// spilled; the spilled-run merge doesn't thread a secondary key through its own machinery,
+    /// so once data has spilled, [`Self::kth_via_merge`] still breaks ties arbitrarily.
+    tie_break: bool,
+    /// Secondary order-by values, parallel to `data` by index (`secondary[i]` breaks ties for
+    /// `data[i]`). Only populated when `tie_break` is set; always empty otherwise.
+    secondary: Vec<Datum>,
+    /// `true` once [`Self::with_rank`] configures the extension mode where a result carries not
+    /// just the picked value but also its 1-based ordinal position in the ordered set, as a
+    /// 2-field `(value, rank)` struct. `false` (the default) keeps standard SQL `percentile_disc`
+    /// behavior of returning the bare value. See [`Self::return_type`] and [`Self::wrap_with_rank`].
+    with_rank: bool,
+    /// `true` once [`Self::with_value_column`] configures the extension where the ordered-set
+    /// rank is determined by one column (buffered in `data`, as usual) but the returned value
+    /// comes from a separately-supplied column instead of `data` itself — for a plan that
+    /// delivers the `WITHIN GROUP` sort key and the aggregated value as distinct, only
+    /// type-coercible columns. Like [`Self::tie_break`], [`Self::get_output`] and
+    /// [`Aggregator::output`] consult it only while nothing has spilled.
+    value_column: bool,
+    /// Value-column data, parallel to `data` by index (`values[i]` is the value returned when
+    /// `data[i]`'s sort key is picked). Only populated when `value_column` is set; always empty
+    /// otherwise.
+    values: Vec<Datum>,
+    /// `true` once [`Self::with_min_max`] configures the extension mode where a result carries the
+    /// picked value together with the minimum and maximum of the whole ordered set, as a 3-field
+    /// `(value, min, max)` struct. `false` (the default) keeps standard SQL `percentile_disc`
+    /// behavior of returning the bare value. Not designed to compose with [`Self::with_rank`] --
+    /// [`Self::wrap_with_min_max`] debug-asserts the two aren't both set, since neither constructor
+    /// is externally composable with the other today and silently nesting one struct inside the
+    /// other's "value" field would be a confusing shape to hand back either way.
+    with_min_max: bool,
+    /// `true` once [`Self::with_weights`] configures a per-value weight, changing rank computation
+    /// from a plain positional rank over the count of buffered values to a cumulative-weight rank
+    /// over the sum of `weights`: the picked value becomes the smallest (in sort order) one whose
+    /// cumulative weight reaches `fraction * total_weight`, matching the usual definition of a
+    /// weighted percentile. Like [`Self::tie_break`], [`Self::get_output`] and
+    /// [`Aggregator::output`] consult it only while nothing has spilled.
+    with_weights: bool,
+    /// Weights, parallel to `data` by index (`weights[i]` is `data[i]`'s weight). Only populated
+    /// when `with_weights` is set; always empty otherwise.
+    weights: Vec<f64>,
+    /// Extra fractions beyond `fractions` itself, set via [`Self::with_multiple_fractions`]: an
+    /// extension beyond standard SQL `percentile_disc`, which only ever takes one fraction direct
+    /// argument, letting a caller request several percentiles (e.g. p50/p90/p99) computed from a
+    /// single buffered scan instead of running one aggregate call per fraction that each re-buffer
+    /// and re-sort the same input. Empty for an ordinary single-fraction call, in which case
+    /// [`Aggregator::output`]/`get_output` behave exactly as before. Non-empty routes both away
+    /// from `output`'s move-out fast path (see its guard) and into `get_output`'s multi-value
+    /// branch, which reports one result per fraction (primary first, then `extra_fractions` in
+    /// order) as a `Some(ScalarImpl::List(...))` instead of a single scalar.
+    extra_fractions: Vec<f64>,
+    /// `true` when `data` is currently known to be fully sorted, letting [`Self::value_for_fraction`]
+    /// and [`Self::output`]'s fast path index into it directly instead of sorting a fresh copy --
+    /// the same purpose [`Self::presorted`] serves for caller-guaranteed arrival order, but derived
+    /// from [`Self::add_datum`] maintaining `data` in sorted position via binary-search insertion
+    /// instead. Only the plain path (no [`Self::tie_break`]/[`Self::value_column`]/
+    /// [`Self::with_weights`]) maintains this: those extension modes append to `data` in arrival
+    /// order to stay in lockstep with their own parallel `secondary`/`values`/`weights` buffers (see
+    /// those fields' doc comments), so `add_datum` falls back to appending and clears this flag
+    /// instead of paying for a search-and-shift insert it can't use lockstep anyway.
+    /// [`Self::remove_datum`] leaves it as is, since its `Vec::remove` preserves order.
+    sorted: bool,
 }
 
 impl EstimateSize for PercentileDisc {
     fn estimated_heap_size(&self) -> usize {
-        self.data
+        // Counts each buffer's own backing allocation (`capacity * size_of::<ScalarImpl>`) as well
+        // as any further heap data a variant like `Utf8` owns beyond that (`estimated_heap_size`),
+        // matching how `Vec<T>: EstimateSize` is defined elsewhere for a `ZeroHeapSize` element —
+        // this can't reuse that blanket impl since `ScalarImpl` itself owns heap data.
+        let elem_size = std::mem::size_of::<ScalarImpl>();
+        let vec_size = |v: &Vec<ScalarImpl>| {
+            v.capacity() * elem_size + v.iter().fold(0, |acc, x| acc + x.estimated_heap_size())
+        };
+        let data_size = vec_size(&self.data);
+        let data_size = self
+            .spilled_runs
             .iter()
-            .fold(0, |acc, x| acc + x.estimated_heap_size())
+            .fold(data_size, |acc, run| acc + vec_size(run));
+        let digest_size = self
+            .digest
+            .as_ref()
+            .map_or(0, |d| d.centroids.capacity() * std::mem::size_of::<Centroid>());
+        data_size + digest_size
     }
 }
 
 impl PercentileDisc {
     pub fn new(fractions: Option<f64>, return_type: DataType) -> Self {
+        Self::with_spill_threshold(fractions, return_type, DEFAULT_SPILL_THRESHOLD)
+    }
+
+    /// Like [`Self::new`], but spills a sorted run to `spilled_runs` as soon as the buffer holds
+    /// `spill_threshold` values, so each sort covers at most `spill_threshold` values. Spilled runs
+    /// are still held in memory (`spilled_runs` is a `Vec` of runs).
+    pub fn with_spill_threshold(
+        fractions: Option<f64>,
+        return_type: DataType,
+        spill_threshold: usize,
+    ) -> Self {
+        Self::with_rounding_and_spill_threshold(
+            fractions,
+            return_type,
+            RoundingPolicy::default(),
+            spill_threshold,
+        )
+    }
+
+    /// Like [`Self::new`], but with an explicit [`RoundingPolicy`] instead of the default `Ceil`.
+    pub fn with_rounding(fractions: Option<f64>, return_type: DataType, rounding: RoundingPolicy) -> Self {
+        Self::with_rounding_and_spill_threshold(
+            fractions,
+            return_type,
+            rounding,
+            DEFAULT_SPILL_THRESHOLD,
+        )
+    }
+
+    pub fn with_rounding_and_spill_threshold(
+        fractions: Option<f64>,
+        return_type: DataType,
+        rounding: RoundingPolicy,
+        spill_threshold: usize,
+    ) -> Self {
         Self {
             fractions,
             return_type,
+            rounding,
             data: vec![],
+            spilled_runs: vec![],
+            spill_threshold,
+            output_cache: RefCell::new(None),
+            recompute_count: Cell::new(0),
+            fraction_resolved: true,
+            ignore_nulls: true,
+            null_count: 0,
+            presorted: false,
+            sort_count: Cell::new(0),
+            comparator: None,
+            memory_budget: None,
+            digest: None,
+            tie_break: false,
+            secondary: vec![],
+            with_rank: false,
+            value_column: false,
+            values: vec![],
+            with_min_max: false,
+            with_weights: false,
+            weights: vec![],
+            extra_fractions: vec![],
+            sorted: true,
         }
     }
 
+    /// Like [`Self::new`], but with an explicit `RESPECT NULLS` (`ignore_nulls: false`) vs
+    /// `IGNORE NULLS` (`ignore_nulls: true`, the default) choice. `RESPECT NULLS` treats buffered
+    /// NULLs as the greatest elements of the ordered set, so e.g. `percentile_disc(1.0)` over
+    /// `[1, 2, NULL]` returns NULL rather than `2`.
+    pub fn with_ignore_nulls(fractions: Option<f64>, return_type: DataType, ignore_nulls: bool) -> Self {
+        Self {
+            ignore_nulls,
+            ..Self::new(fractions, return_type)
+        }
+    }
+
+    /// Like [`Self::new`], but with a hint that rows will arrive already sorted by the order-by
+    /// column (e.g. because the plan feeds this aggregator from a sorted scan), so
+    /// [`Aggregator::output`]'s move-out fast path can skip re-sorting them. Passing `true` when
+    /// arrival order isn't actually sorted trips a debug assertion in [`Self::add_datum`]; in
+    /// release builds it silently produces a wrong answer, same as any other broken invariant the
+    /// caller is responsible for.
+    ///
+    /// Scope note, applying to this constructor and every other `with_*` constructor below it
+    /// (`with_comparator`, `with_tie_break`, `with_rank`, `with_value_column`, `with_min_max`,
+    /// `with_weights`, `with_multiple_fractions`, `with_memory_budget`): none of them are
+    /// reachable from SQL today. [`build`] is the only `#[build_aggregate]` entry point into this
+    /// file, it never calls any of them, and there is no `AggKind`/`PbType` variant or planner/
+    /// binder syntax that would let a query construct one. They're directly-embeddable building
+    /// blocks exercised by this file's own unit tests, not shipped SQL features -- wiring any one
+    /// of them through the catalog and frontend into a usable aggregate is real, separate work
+    /// that hasn't been done, not a detail this constructor's doc comment can paper over.
+    pub fn with_presorted_hint(fractions: Option<f64>, return_type: DataType, presorted: bool) -> Self {
+        Self {
+            presorted,
+            ..Self::new(fractions, return_type)
+        }
+    }
+
+    /// Like [`Self::new`], but with a comparator overriding [`DefaultOrdered`]'s byte-order
+    /// comparison, for a `varchar` order-by column that needs locale-aware (`COLLATE`) ordering.
+    /// Not reachable from SQL today -- see the scope note on [`Self::with_presorted_hint`]; there's
+    /// no `Collation` type anywhere in this codebase and `AggCall` doesn't carry a collation for
+    /// `build` to read either way. [`Aggregator::output`], [`Self::get_output`] and `add_datum`'s
+    /// sorted insert all compare with it; only the spilled runs (their sort and the merge in
+    /// [`Self::kth_via_merge`]) still compare by byte order, since neither has a place to carry a
+    /// `dyn Fn` comparator through its own machinery.
+    pub fn with_comparator(
+        fractions: Option<f64>,
+        return_type: DataType,
+        comparator: Arc<dyn Fn(&ScalarImpl, &ScalarImpl) -> Ordering + Send + Sync>,
+    ) -> Self {
+        Self {
+            comparator: Some(comparator),
+            ..Self::new(fractions, return_type)
+        }
+    }
+
+    /// Like [`Self::new`], but with a secondary order-by column: when two buffered values tie
+    /// under the primary comparison, [`Aggregator::output`]'s fast path breaks the tie by
+    /// comparing the corresponding secondary value instead of leaving the pick to arbitrary
+    /// sort-stable order, giving deterministic results across runs for reproducible tests and
+    /// SQL semantics that specify a full order (e.g. `ORDER BY x, y`).
+    ///
+    /// Not reachable from SQL today -- see the scope note on [`Self::with_presorted_hint`]; there's
+    /// no second order-by argument `build` can thread through either way (`AggCall` only carries
+    /// the single `percentile_disc(*)` argument).
+    pub fn with_tie_break(fractions: Option<f64>, return_type: DataType) -> Self {
+        Self {
+            tie_break: true,
+            ..Self::new(fractions, return_type)
+        }
+    }
+
+    /// Like [`Self::new`], but an extension beyond standard SQL `percentile_disc`: instead of just
+    /// the picked value, results become a 2-field `(value, rank)` struct, where `rank` is the
+    /// picked element's 1-based position in the ordered set. Gated behind this opt-in constructor
+    /// so default behavior (and `return_type`) is unchanged for ordinary `percentile_disc` calls.
+    /// Covered by the scope note on [`Self::with_presorted_hint`] like the other `with_*`
+    /// constructors -- there's no SQL syntax for this extension either, so it's only reachable by
+    /// calling this constructor directly. See [`Self::wrap_with_rank`].
+    pub fn with_rank(fractions: Option<f64>, return_type: DataType) -> Self {
+        Self {
+            with_rank: true,
+            ..Self::new(fractions, return_type)
+        }
+    }
+
+    /// Like [`Self::new`], but for a `WITHIN GROUP` sort key that's a separate, only
+    /// type-coercible column from the aggregated value: rows are fed through
+    /// [`Self::add_datum_with_value_column`] instead of [`Self::add_datum`], buffering the sort
+    /// key in `data` (as usual, so ranking logic is unchanged) and the value in `values`.
+    /// `return_type` here describes the *value* column, not the sort key, which may be any
+    /// orderable type.
+    ///
+    /// Not reachable from SQL today -- see the scope note on [`Self::with_presorted_hint`]; there's
+    /// no separate value-column argument `build` can thread through either way (`AggCall` only
+    /// carries the single `percentile_disc(*)` argument).
+    pub fn with_value_column(fractions: Option<f64>, return_type: DataType) -> Self {
+        Self {
+            value_column: true,
+            ..Self::new(fractions, return_type)
+        }
+    }
+
+    /// Like [`Self::new`], but an extension beyond standard SQL `percentile_disc`: instead of just
+    /// the picked value, results become a 3-field `(value, min, max)` struct, where `min`/`max`
+    /// are the minimum and maximum of the whole ordered set the percentile was computed over. Lets
+    /// a caller get a percentile and the data's range from a single aggregate call instead of
+    /// three (`percentile_disc`, `min`, `max`). Gated behind this opt-in constructor so default
+    /// behavior (and `return_type`) is unchanged for ordinary `percentile_disc` calls. Covered by
+    /// the scope note on [`Self::with_presorted_hint`] like the other `with_*` constructors --
+    /// there's no SQL syntax for this extension either, so it's only reachable by calling this
+    /// constructor directly. See [`Self::wrap_with_min_max`].
+    pub fn with_min_max(fractions: Option<f64>, return_type: DataType) -> Self {
+        Self {
+            with_min_max: true,
+            ..Self::new(fractions, return_type)
+        }
+    }
+
+    /// Like [`Self::new`], but an extension beyond standard SQL `percentile_disc`: instead of
+    /// treating every buffered value as equally significant, each value is paired with a weight,
+    /// and the picked value becomes the smallest (in sort order) one whose cumulative weight
+    /// reaches `fraction * total_weight` — the usual generalization of a percentile to weighted
+    /// data (e.g. a histogram's bucket midpoints weighted by bucket count). Gated behind this
+    /// opt-in constructor so default behavior is unchanged for ordinary `percentile_disc` calls,
+    /// which implicitly weight every value as `1`.
+    ///
+    /// Not reachable from SQL today -- see the scope note on [`Self::with_presorted_hint`]. The
+    /// request this was built for asked for a separately-named `WeightedPercentileDisc` SQL
+    /// aggregate; what actually exists is this constructor plus the extension machinery below it,
+    /// with no `AggKind`/`PbType` variant and no `#[build_aggregate]` entry point that would let a
+    /// query reach it. Making it a real aggregate needs a new function name recognized by the
+    /// binder, a catalog/proto `AggKind` variant, and a `build_aggregate` wiring into this
+    /// constructor -- none of which this change does; that's separate, unimplemented work, not a
+    /// detail this doc comment should gloss over.
+    pub fn with_weights(fractions: Option<f64>, return_type: DataType) -> Self {
+        Self {
+            with_weights: true,
+            ..Self::new(fractions, return_type)
+        }
+    }
+
+    /// Like [`Self::new`], but reports one result per fraction instead of a single scalar -- an
+    /// extension beyond standard SQL `percentile_disc`, which only ever takes one fraction direct
+    /// argument. `fractions` must be non-empty; the first becomes [`Self::fractions`] (the primary
+    /// fraction every other method already expects), and the rest become `extra_fractions`. Covered
+    /// by the scope note on [`Self::with_presorted_hint`] like the other `with_*` constructors --
+    /// `AggCall` only ever carries the single direct fraction argument, so this is only reachable
+    /// by calling this constructor directly. See `extra_fractions`'s field doc for how the result
+    /// is shaped.
+    pub fn with_multiple_fractions(fractions: Vec<f64>, return_type: DataType) -> Self {
+        assert!(
+            !fractions.is_empty(),
+            "with_multiple_fractions requires at least one fraction"
+        );
+        let mut fractions = fractions.into_iter();
+        let primary = fractions.next();
+        Self {
+            extra_fractions: fractions.collect(),
+            ..Self::new(primary, return_type)
+        }
+    }
+
+    /// Compares two buffered values using [`Self::comparator`] if one was supplied, falling back
+    /// to byte order via [`DefaultOrdered`] otherwise.
+    fn compare(&self, a: &ScalarImpl, b: &ScalarImpl) -> Ordering {
+        match &self.comparator {
+            Some(comparator) => comparator(a, b),
+            None => DefaultOrdered(a.clone()).cmp(&DefaultOrdered(b.clone())),
+        }
+    }
+
+    /// Wraps `value` with its 0-indexed `rank`'s 1-based ordinal position into the `(value, rank)`
+    /// struct configured by [`Self::with_rank`]; a no-op returning `value` unchanged when that
+    /// flag isn't set (the default).
+    fn wrap_with_rank(&self, value: ScalarImpl, rank: usize) -> ScalarImpl {
+        if !self.with_rank {
+            return value;
+        }
+        ScalarImpl::Struct(StructValue::new(vec![
+            Some(value),
+            Some(ScalarImpl::from(rank as i64 + 1)),
+        ]))
+    }
+
+    /// Finds the minimum and maximum among all exact buffered values (`data` plus every spilled
+    /// run) by [`Self::compare`], for [`Self::with_min_max`]. Returns `None` when there's nothing
+    /// exact to compare — either genuinely empty, or [`Self::digest`] has already taken over and
+    /// folded the original values away into an approximate summary that has no min/max to report.
+    fn total_min_max(&self) -> Option<(ScalarImpl, ScalarImpl)> {
+        let mut iter = self.data.iter().chain(self.spilled_runs.iter().flatten());
+        let first = iter.next()?.clone();
+        Some(iter.fold((first.clone(), first), |(min, max), v| {
+            let min = if self.compare(v, &min) == Ordering::Less {
+                v.clone()
+            } else {
+                min
+            };
+            let max = if self.compare(v, &max) == Ordering::Greater {
+                v.clone()
+            } else {
+                max
+            };
+            (min, max)
+        }))
+    }
+
+    /// Wraps `value` with `min_max` into the `(value, min, max)` struct configured by
+    /// [`Self::with_min_max`]; a no-op returning `value` unchanged when that flag isn't set (the
+    /// default).
+    fn wrap_with_min_max(&self, value: ScalarImpl, min_max: Option<(ScalarImpl, ScalarImpl)>) -> ScalarImpl {
+        if !self.with_min_max {
+            return value;
+        }
+        debug_assert!(
+            !self.with_rank,
+            "with_rank and with_min_max are not designed to compose; wrapping with_rank's \
+             (value, rank) struct as with_min_max's own value field would be a confusing shape \
+             to hand back"
+        );
+        // `total_min_max` is `None` once the digest has taken over: no exact values are left to
+        // compare, so min and max are reported as NULL.
+        let (min, max) = match min_max {
+            Some((min, max)) => (Some(min), Some(max)),
+            None => (None, None),
+        };
+        ScalarImpl::Struct(StructValue::new(vec![Some(value), min, max]))
+    }
+
+    /// Like [`Self::new`], but with a byte budget: once buffered data grows past `memory_budget`,
+    /// `add_datum` transparently switches from exact buffering to an approximate [`TDigest`]
+    /// summary, so a group with unexpectedly huge cardinality degrades to an approximate answer
+    /// under a reported [`Self::error_bound`] instead of the query being killed for memory use.
+    /// This *is* this module's approximate streaming percentile alongside exact `percentile_disc`
+    /// — it isn't a separate aggregate. [`Self::with_rank`] still applies once switched over (its
+    /// rank is approximated the same as the value itself), and [`Self::with_min_max`] reports NULL
+    /// min and max after the switch, since the digest keeps no exact values. [`Self::with_tie_break`],
+    /// [`Self::with_value_column`] and [`Self::with_weights`] don't compose with a budget (see the
+    /// debug assertion in [`Self::maybe_switch_to_digest`]).
+    ///
+    /// Only plain numeric `return_type`s (the integer and floating-point types) can be
+    /// approximated this way — a digest has nothing to fall back to for e.g. `varchar` — so for
+    /// any other type this budget is checked but never actually triggers a switchover.
+    ///
+    /// Covered by the scope note on [`Self::with_presorted_hint`] like the other `with_*`
+    /// constructors -- there's no planner-set memory budget threaded through `AggCall` today, so
+    /// this is only reachable by calling this constructor directly.
+    pub fn with_memory_budget(
+        fractions: Option<f64>,
+        return_type: DataType,
+        memory_budget: usize,
+    ) -> Self {
+        Self {
+            memory_budget: Some(memory_budget),
+            ..Self::new(fractions, return_type)
+        }
+    }
+
+    /// Once [`Self::with_memory_budget`] has switched this aggregator over to its approximate
+    /// digest, the fraction of total weight the digest's widest centroid represents — an upper
+    /// bound on how far off a returned quantile's rank can be from the requested fraction.
+    /// `None` before any switchover, when results are still exact.
+    pub fn error_bound(&self) -> Option<f64> {
+        self.digest.as_ref().map(TDigest::error_bound)
+    }
+
+    /// Switches to the approximate digest if `memory_budget` is set and just-exceeded, folding in
+    /// everything buffered so far. A no-op if already switched over, no budget was set, budget
+    /// isn't yet exceeded, or the buffered type isn't one [`scalar_to_f64`] can approximate.
+    fn maybe_switch_to_digest(&mut self) {
+        if self.digest.is_some() {
+            return;
+        }
+        let Some(budget) = self.memory_budget else {
+            return;
+        };
+        if EstimateSize::estimated_heap_size(self) <= budget {
+            return;
+        }
+        debug_assert!(
+            !self.tie_break && !self.value_column && !self.with_weights,
+            "with_memory_budget isn't designed to compose with with_tie_break/with_value_column/\
+             with_weights -- switching to the digest clears `data`/`spilled_runs` but not \
+             `secondary`/`values`/`weights`, which would leave those side buffers misaligned by \
+             index with whatever the digest reports afterwards"
+        );
+
+        let mut values = Vec::with_capacity(self.total_len());
+        for v in self.data.iter().chain(self.spilled_runs.iter().flatten()) {
+            let Some(f) = scalar_to_f64(v) else {
+                // Not an approximable type; stay exact even though we're over budget.
+                return;
+            };
+            values.push(f);
+        }
+
+        let mut digest = TDigest::new(DIGEST_MAX_CENTROIDS);
+        for v in values {
+            digest.insert(v);
+        }
+        self.digest = Some(digest);
+        self.data.clear();
+        self.spilled_runs.clear();
+    }
+
+    /// Builds a [`PercentileDisc`] whose fraction is not yet known, for the (currently
+    /// unreachable from SQL) case of a prepared-statement parameter that a caller wants to bind
+    /// after construction. [`AggCall::direct_args`] holds already-resolved [`LiteralExpression`]s
+    /// by the time `build` runs — the frontend binder substitutes parameters into literals before
+    /// physical plan construction — so `build` itself never actually produces one of these today;
+    /// this exists for embedders that construct a [`PercentileDisc`] directly and want to reuse
+    /// one physical aggregator shape across several bound values. [`Self::resolve_fraction`] must
+    /// be called before [`Aggregator::update`], or it returns an error.
+    pub fn new_with_deferred_fraction(return_type: DataType) -> Self {
+        Self {
+            fractions: None,
+            return_type,
+            rounding: RoundingPolicy::default(),
+            data: vec![],
+            spilled_runs: vec![],
+            spill_threshold: DEFAULT_SPILL_THRESHOLD,
+            output_cache: RefCell::new(None),
+            recompute_count: Cell::new(0),
+            fraction_resolved: false,
+            ignore_nulls: true,
+            null_count: 0,
+            presorted: false,
+            sort_count: Cell::new(0),
+            comparator: None,
+            memory_budget: None,
+            digest: None,
+            tie_break: false,
+            secondary: vec![],
+            with_rank: false,
+            value_column: false,
+            values: vec![],
+            with_min_max: false,
+            with_weights: false,
+            weights: vec![],
+            extra_fractions: vec![],
+            sorted: true,
+        }
+    }
+
+    /// Resolves a fraction deferred via [`Self::new_with_deferred_fraction`] from a bound
+    /// prepared-statement parameter. Must be called before any rows are accumulated. Errors if a
+    /// fraction was already resolved, whether by `build` seeing a literal or by a prior call.
+    pub fn resolve_fraction(&mut self, fraction: Option<f64>) -> Result<()> {
+        if self.fraction_resolved {
+            return Err(crate::ExprError::InvalidParam {
+                name: "fraction",
+                reason: "percentile_disc fraction was already resolved".into(),
+            });
+        }
+        self.fractions = fraction;
+        self.fraction_resolved = true;
+        Ok(())
+    }
+
+    fn ensure_fraction_resolved(&self) -> Result<()> {
+        if !self.fraction_resolved {
+            return Err(crate::ExprError::InvalidParam {
+                name: "fraction",
+                reason: "percentile_disc fraction parameter is unbound".into(),
+            });
+        }
+        Ok(())
+    }
+
+    /// A cheap order-sensitive fingerprint of the buffered data, used to detect whether
+    /// `get_output` can reuse the last computed result.
+    fn data_fingerprint(&self) -> u64 {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        self.spilled_runs.len().hash(&mut hasher);
+        for run in &self.spilled_runs {
+            run.len().hash(&mut hasher);
+        }
+        self.data.len().hash(&mut hasher);
+        for v in &self.data {
+            // `ScalarImpl` doesn't implement `Hash`, so fall back to its `Debug` rendering; this
+            // is only used to short-circuit recomputation, not for correctness.
+            format!("{v:?}").hash(&mut hasher);
+        }
+        self.null_count.hash(&mut hasher);
+        if let Some(digest) = &self.digest {
+            digest.count().hash(&mut hasher);
+            digest.centroids.len().hash(&mut hasher);
+            for c in &digest.centroids {
+                c.mean.to_bits().hash(&mut hasher);
+                c.weight.to_bits().hash(&mut hasher);
+            }
+        }
+        hasher.finish()
+    }
+
+    /// Number of times [`Aggregator::get_output`] actually recomputed the result rather than
+    /// returning a cached value. Exposed for tests only.
+    #[cfg(test)]
+    pub fn recompute_count(&self) -> usize {
+        self.recompute_count.get()
+    }
+
+    /// Number of times buffered data was actually sorted. Exposed for tests only.
+    #[cfg(test)]
+    pub fn sort_count(&self) -> usize {
+        self.sort_count.get()
+    }
+
     fn add_datum(&mut self, datum_ref: DatumRef<'_>) {
-        if let Some(datum) = datum_ref.to_owned_datum() {
-            self.data.push(datum);
+        match classify_ordered_set_input(datum_ref, self.ignore_nulls) {
+            OrderedSetInput::Value(datum) => {
+                self.debug_assert_homogeneous(&datum);
+                if let Some(digest) = &mut self.digest {
+                    // Once switched over, a non-approximable type can no longer occur here:
+                    // `debug_assert_homogeneous` above guarantees every value shares the same
+                    // variant as whatever was already folded into the digest.
+                    let value = scalar_to_f64(&datum)
+                        .expect("digest only ever holds a type scalar_to_f64 accepts");
+                    digest.insert(value);
+                    return;
+                }
+                if self.presorted {
+                    debug_assert!(
+                        self.data
+                            .last()
+                            .map_or(true, |prev| DefaultOrdered(prev.clone())
+                                <= DefaultOrdered(datum.clone())),
+                        "percentile_disc was hinted presorted but received data out of order"
+                    );
+                    self.data.push(datum);
+                } else if !self.tie_break && !self.value_column && !self.with_weights {
+                    // Keep `data` sorted incrementally so `value_for_fraction`/`output` can index
+                    // straight into it instead of re-sorting from scratch on every emission -- the
+                    // "sorted insert" alternative to a `BTreeMap`-based buffer.
+                    let pos = self
+                        .data
+                        .partition_point(|existing| self.compare(existing, &datum) != Ordering::Greater);
+                    self.data.insert(pos, datum);
+                } else {
+                    // `secondary`/`values`/`weights` are appended in lockstep with `data` by their
+                    // own wrapper (e.g. `add_datum_with_tie_break`), so `data` must keep arrival
+                    // order here too, or the parallel buffers would drift out of index-correspondence.
+                    self.data.push(datum);
+                    self.sorted = false;
+                }
+                self.maybe_switch_to_digest();
+            }
+            OrderedSetInput::RespectedNull => self.null_count += 1,
+            OrderedSetInput::IgnoredNull => {}
+        }
+        if self.digest.is_none() && self.data.len() >= self.spill_threshold {
+            self.spill_current_run();
         }
     }
+
+    /// Undoes a previous [`Self::add_datum`] for a `Delete`/`UpdateDelete` row from a streaming
+    /// changelog. Only supports the plain (no tie-break/value-column/weight extension) buffered
+    /// case: once data has spilled into sorted runs or switched over to an approximate [`TDigest`], there's
+    /// no way to identify and remove one specific value from either representation, so this
+    /// returns an error rather than silently producing a wrong answer.
+    fn remove_datum(&mut self, datum_ref: DatumRef<'_>) -> Result<()> {
+        if !self.spilled_runs.is_empty() || self.digest.is_some() {
+            return Err(crate::ExprError::Internal(anyhow::anyhow!(
+                "percentile_disc cannot retract a value once its buffer has spilled into sorted runs or \
+                 switched over to an approximate digest"
+            )));
+        }
+        match datum_ref.to_owned_datum() {
+            Some(datum) => {
+                let pos = self
+                    .data
+                    .iter()
+                    .position(|v| self.compare(v, &datum) == Ordering::Equal)
+                    .ok_or_else(|| {
+                        crate::ExprError::Internal(anyhow::anyhow!(
+                            "percentile_disc received a retraction for a value that was never \
+                             buffered (or was already retracted)"
+                        ))
+                    })?;
+                // `remove` rather than `swap_remove`: shifting the tail keeps `data` in sorted
+                // order, which the `presorted`/`sorted` paths index into directly.
+                self.data.remove(pos);
+            }
+            None if !self.ignore_nulls => {
+                self.null_count = self.null_count.checked_sub(1).ok_or_else(|| {
+                    crate::ExprError::Internal(anyhow::anyhow!(
+                        "percentile_disc received a NULL retraction with no buffered NULL left"
+                    ))
+                })?;
+            }
+            None => {}
+        }
+        *self.output_cache.borrow_mut() = None;
+        Ok(())
+    }
+
+    /// Like [`Self::add_datum`], but also buffers `secondary_ref` in lockstep for
+    /// [`Self::with_tie_break`] to compare on a tie. Only called when `tie_break` is set, so
+    /// `secondary` always ends up the same length as `data`.
+    fn add_datum_with_tie_break(&mut self, datum_ref: DatumRef<'_>, secondary_ref: DatumRef<'_>) {
+        let is_non_null = datum_ref.is_some();
+        self.add_datum(datum_ref);
+        // Once switched over to `digest`, exactness (and with it, tie-breaking) is already given
+        // up, and `data` stops growing — so `secondary` must stop growing too, or it would drift
+        // out of lockstep with `data`.
+        if is_non_null && self.digest.is_none() {
+            self.secondary.push(secondary_ref.to_owned_datum());
+        }
+    }
+
+    /// Like [`Self::add_datum`], but buffers `value_ref` in lockstep in `values` for
+    /// [`Self::with_value_column`], instead of returning the sort key itself. Only called when
+    /// `value_column` is set, so `values` always ends up the same length as `data`.
+    fn add_datum_with_value_column(&mut self, sort_key_ref: DatumRef<'_>, value_ref: DatumRef<'_>) {
+        let is_non_null = sort_key_ref.is_some();
+        self.add_datum(sort_key_ref);
+        // Same rationale as `add_datum_with_tie_break`: once switched over to `digest`, `data`
+        // stops growing, so `values` must stop growing too.
+        if is_non_null && self.digest.is_none() {
+            self.values.push(value_ref.to_owned_datum());
+        }
+    }
+
+    /// Like [`Self::add_datum`], but buffers `weight_ref` in lockstep in `weights` for
+    /// [`Self::with_weights`]. A NULL or non-numeric weight is treated as `1.0`, same as an
+    /// unweighted value. A negative weight has no sensible meaning for a cumulative-weight
+    /// threshold (it would push `threshold` past `total_weight`, or below zero, depending on how
+    /// many negative weights precede it), so it's rejected outright rather than silently folded
+    /// into the sum -- zero is still accepted, since a zero-weighted value simply never
+    /// contributes to crossing the threshold. Only called when `with_weights` is set, so `weights`
+    /// always ends up the same length as `data`.
+    fn add_datum_with_weight(
+        &mut self,
+        datum_ref: DatumRef<'_>,
+        weight_ref: DatumRef<'_>,
+    ) -> Result<()> {
+        let is_non_null = datum_ref.is_some();
+        self.add_datum(datum_ref);
+        // Same rationale as `add_datum_with_tie_break`: once switched over to `digest`, `data`
+        // stops growing, so `weights` must stop growing too.
+        if is_non_null && self.digest.is_none() {
+            let weight = weight_ref
+                .to_owned_datum()
+                .as_ref()
+                .and_then(scalar_to_f64)
+                .unwrap_or(1.0);
+            if weight < 0.0 {
+                return Err(crate::ExprError::InvalidParam {
+                    name: "weight",
+                    reason: format!("percentile_disc weights must be non-negative, got {weight}").into(),
+                });
+            }
+            self.weights.push(weight);
+        }
+        Ok(())
+    }
+
+    /// In debug builds, checks that `datum` is the same runtime variant as everything already
+    /// buffered, so a plan bug that feeds mixed types (e.g. `Int32` and `Utf8` in the same group)
+    /// fails clearly here instead of panicking deep inside the sort comparator, which can't tell
+    /// "incomparable variants" apart from a real bug in [`DefaultOrdered`]. Release builds trust
+    /// the caller to guarantee homogeneity, per [`Aggregator::update`]'s contract.
+    fn debug_assert_homogeneous(&self, datum: &ScalarImpl) {
+        let existing = self
+            .data
+            .first()
+            .or_else(|| self.spilled_runs.first().and_then(|run| run.first()));
+        if let Some(existing) = existing {
+            debug_assert_eq!(
+                existing.get_ident(),
+                datum.get_ident(),
+                "percentile_disc received mixed-type data: expected {}, got {}",
+                existing.get_ident(),
+                datum.get_ident(),
+            );
+        }
+    }
+
+    fn spill_current_run(&mut self) {
+        if self.data.is_empty() {
+            return;
+        }
+        // Spilled runs (and `kth_via_merge`'s merge over them) are always ordered by
+        // `DefaultOrdered`, not `Self::comparator` -- so `self.sorted`, which tracks order under
+        // whichever of the two `Self::compare` actually used while incrementally inserting, is only
+        // known to line up with the order this needs when no comparator overrides the default.
+        if self.comparator.is_some() || !self.sorted {
+            self.data.sort_unstable_by(|a, b| {
+                DefaultOrdered(a.clone()).cmp(&DefaultOrdered(b.clone()))
+            });
+            self.sort_count.set(self.sort_count.get() + 1);
+        }
+        self.spilled_runs.push(std::mem::take(&mut self.data));
+        // `secondary`/`values`/`weights` only ever track the still-unspilled tail of `data`
+        // (tie-breaking, the separate value column, and weighting don't apply past a spill
+        // anyway, see `tie_break`'s, `value_column`'s and `with_weights`'s doc comments), so once
+        // a run spills, anything buffered for it in any of them is no longer useful and would
+        // otherwise drift out of lockstep with the fresh `data` that accumulates after this point.
+        self.secondary.clear();
+        self.values.clear();
+        self.weights.clear();
+    }
+
+    /// Number of non-NULL values accumulated so far, across the in-memory buffer and all spilled
+    /// runs, for the executor to report to the optimizer as a cardinality hint.
+    pub fn accumulated_count(&self) -> usize {
+        self.total_len()
+    }
+
+    /// Number of non-NULL buffered values across the in-memory buffer, all spilled runs, and (once
+    /// switched over) the digest.
+    fn total_len(&self) -> usize {
+        self.data.len()
+            + self.spilled_runs.iter().map(Vec::len).sum::<usize>()
+            + self.digest.as_ref().map_or(0, |d| d.count() as usize)
+    }
+
+    /// Size of the ordered set that rank arithmetic ranks over: `total_len` plus, when
+    /// `ignore_nulls` is `false`, the buffered NULLs sitting past every non-NULL value.
+    fn ordered_len(&self) -> usize {
+        self.total_len() + self.null_count
+    }
+
+    /// Finds the 0-indexed `target`-th smallest value by an external k-way merge over the
+    /// spilled sorted runs plus the (freshly sorted) in-memory buffer, without concatenating them
+    /// into a single in-memory vector.
+    fn kth_via_merge(&self, target: usize) -> ScalarImpl {
+        let sorted_tail = sort_by_default_order(&self.data);
+
+        let mut cursors: Vec<(usize, &[ScalarImpl])> = self
+            .spilled_runs
+            .iter()
+            .map(|run| (0usize, run.as_slice()))
+            .chain(std::iter::once((0usize, sorted_tail.as_slice())))
+            .collect();
+
+        let mut heap: BinaryHeap<Reverse<(DefaultOrdered<ScalarImpl>, usize)>> = BinaryHeap::new();
+        for (run_idx, (pos, run)) in cursors.iter().enumerate() {
+            if let Some(v) = run.get(*pos) {
+                heap.push(Reverse((DefaultOrdered(v.clone()), run_idx)));
+            }
+        }
+
+        let mut remaining = target;
+        loop {
+            let Reverse((value, run_idx)) = heap.pop().expect("target within total_len");
+            let (pos, run) = &mut cursors[run_idx];
+            *pos += 1;
+            if remaining == 0 {
+                return value.into_inner();
+            }
+            remaining -= 1;
+            if let Some(v) = run.get(*pos) {
+                heap.push(Reverse((DefaultOrdered(v.clone()), run_idx)));
+            }
+        }
+    }
+}
+
+/// Sorts a clone of `data` by [`DefaultOrdered`] order. The "sort the buffered values, then index
+/// by rank" step shared by every place in this module (and [`super::median::Median`]) that needs
+/// an exact rank picked out of the full ordered set without a locale-aware comparator override:
+/// [`multi_percentile_disc`], [`PercentileDisc::kth_via_merge`]'s in-memory tail, and `median`'s
+/// middle-element(s) lookup. [`PercentileDisc::value_for_fraction`]'s own non-presorted branch
+/// intentionally doesn't use this -- it needs `self.compare`, which may be a caller-supplied
+/// comparator overriding [`DefaultOrdered`] (see [`PercentileDisc::with_comparator`]), not this
+/// function's fixed one.
+pub(crate) fn sort_by_default_order(data: &[ScalarImpl]) -> Vec<ScalarImpl> {
+    let mut sorted = data.to_vec();
+    sorted.sort_unstable_by(|a, b| DefaultOrdered(a.clone()).cmp(&DefaultOrdered(b.clone())));
+    sorted
+}
+
+/// Answers several `percentile_disc` fractions against the same data with a single sort, for
+/// dashboards computing e.g. p50/p90/p99 as separate aggregate columns over the same `WITHIN
+/// GROUP` ordering. Unlike [`PercentileDisc::with_multiple_fractions`]'s multi-fraction form (which returns
+/// one array-typed column), this targets several independent scalar-typed columns that happen to
+/// share a sort.
+pub fn multi_percentile_disc(data: &[ScalarImpl], fractions: &[f64]) -> Vec<Option<ScalarImpl>> {
+    if data.is_empty() {
+        return vec![None; fractions.len()];
+    }
+    let sorted = sort_by_default_order(data);
+    fractions
+        .iter()
+        .map(|&fraction| Some(sorted[RoundingPolicy::Ceil.rank(fraction, sorted.len())].clone()))
+        .collect()
 }
 
 #[async_trait::async_trait]
 impl Aggregator for PercentileDisc {
     fn return_type(&self) -> DataType {
-        self.return_type.clone()
+        if self.with_rank {
+            DataType::Struct(StructType::unnamed(vec![
+                self.return_type.clone(),
+                DataType::Int64,
+            ]))
+        } else if self.with_min_max {
+            DataType::Struct(StructType::unnamed(vec![
+                self.return_type.clone(),
+                self.return_type.clone(),
+                self.return_type.clone(),
+            ]))
+        } else if !self.extra_fractions.is_empty() {
+            DataType::List(Box::new(self.return_type.clone()))
+        } else {
+            self.return_type.clone()
+        }
     }
 
     async fn update(&mut self, input: &StreamChunk) -> Result<()> {
-        for (_, row) in input.rows() {
-            self.add_datum(row.datum_at(0));
+        self.ensure_fraction_resolved()?;
+        for (op, row) in input.rows() {
+            if matches!(op, Op::Delete | Op::UpdateDelete) {
+                // Retraction is only implemented for the plain buffered case -- see
+                // `remove_datum`'s doc comment for why the spilled/digest states aren't
+                // supported. Erroring out (rather than silently only removing from `data`) when
+                // an extension is in play avoids leaving `data` desynchronized from whichever
+                // side buffer (`secondary`/`values`/`weights`) that extension also maintains.
+                if self.value_column || self.tie_break || self.with_weights {
+                    return Err(crate::ExprError::Internal(anyhow::anyhow!(
+                        "percentile_disc retraction isn't supported together with the tie-break, \
+                         value-column, or weight extensions"
+                    )));
+                }
+                self.remove_datum(row.datum_at(0))?;
+            } else if self.value_column {
+                self.add_datum_with_value_column(row.datum_at(0), row.datum_at(1));
+            } else if self.tie_break {
+                self.add_datum_with_tie_break(row.datum_at(0), row.datum_at(1));
+            } else if self.with_weights {
+                self.add_datum_with_weight(row.datum_at(0), row.datum_at(1))?;
+            } else {
+                self.add_datum(row.datum_at(0));
+            }
         }
         Ok(())
     }
 
     async fn update_range(&mut self, input: &StreamChunk, range: Range<usize>) -> Result<()> {
-        for (_, row) in input.rows_in(range) {
-            self.add_datum(row.datum_at(0));
+        self.ensure_fraction_resolved()?;
+        for (op, row) in input.rows_in(range) {
+            if matches!(op, Op::Delete | Op::UpdateDelete) {
+                if self.value_column || self.tie_break || self.with_weights {
+                    return Err(crate::ExprError::Internal(anyhow::anyhow!(
+                        "percentile_disc retraction isn't supported together with the tie-break, \
+                         value-column, or weight extensions"
+                    )));
+                }
+                self.remove_datum(row.datum_at(0))?;
+            } else if self.value_column {
+                self.add_datum_with_value_column(row.datum_at(0), row.datum_at(1));
+            } else if self.tie_break {
+                self.add_datum_with_tie_break(row.datum_at(0), row.datum_at(1));
+            } else if self.with_weights {
+                self.add_datum_with_weight(row.datum_at(0), row.datum_at(1))?;
+            } else {
+                self.add_datum(row.datum_at(0));
+            }
+        }
+        Ok(())
+    }
+
+    /// Unlike the default (which wraps `input` into a [`StreamChunk`] just to unwrap it again),
+    /// buffers straight from `input`'s columns: a [`DataChunk`] carries no op column to interpret
+    /// either way, so there's nothing the [`StreamChunk`] detour would have bought here. As with
+    /// the trait method it overrides (see [`Aggregator::update_batch`]'s doc), neither batch
+    /// executor calls this today, so the savings are only exercised by this file's own tests.
+    async fn update_batch(&mut self, input: &DataChunk) -> Result<()> {
+        self.ensure_fraction_resolved()?;
+        for row in input.rows() {
+            if self.value_column {
+                self.add_datum_with_value_column(row.datum_at(0), row.datum_at(1));
+            } else if self.tie_break {
+                self.add_datum_with_tie_break(row.datum_at(0), row.datum_at(1));
+            } else if self.with_weights {
+                self.add_datum_with_weight(row.datum_at(0), row.datum_at(1))?;
+            } else {
+                self.add_datum(row.datum_at(0));
+            }
         }
         Ok(())
     }
 
+    /// Rust's aliasing rules already give the borrow/mutate contract this needs: `get_output`
+    /// takes `&self`, so no caller can hold a live snapshot from it while another call to
+    /// `&mut self` `update`/`update_range` is in flight — that would require an outstanding
+    /// mutable borrow to coexist with a shared one, which doesn't compile. What a caller *can* do
+    /// is interleave whole calls (`get_output`, then `update`, then `get_output` again); each
+    /// call still sees a consistent point-in-time snapshot, never a torn read, because
+    /// `output_cache` is invalidated by `data_fingerprint`, which changes as soon as `update`
+    /// appends anything.
+    ///
+    /// Unlike the `with_*` constructors added later in this file, this contract is enforced by
+    /// the borrow checker itself, not by a flag nobody outside this file's own tests can set --
+    /// there's no unreachable-from-SQL surface here to flag.
     fn get_output(&self) -> Result<Datum> {
-        Ok(if let Some(fractions) = self.fractions && !self.data.is_empty() {
-            let rn = fractions * self.data.len() as f64;
-            if fractions == 0.0 {
-                Some(self.data[0].clone())
+        let fingerprint = self.data_fingerprint();
+        if let Some((cached_fingerprint, cached)) = self.output_cache.borrow().as_ref() {
+            if *cached_fingerprint == fingerprint {
+                return Ok(cached.clone());
+            }
+        }
+
+        self.recompute_count.set(self.recompute_count.get() + 1);
+        let total_len = self.total_len();
+        let ordered_len = self.ordered_len();
+        let min_max = self.with_min_max.then(|| self.total_min_max()).flatten();
+        let result = if let Some(fractions) = self.fractions && ordered_len != 0 {
+            let rank = self.rounding.rank(fractions, ordered_len);
+            let value = self.value_for_fraction(fractions, total_len, ordered_len);
+            let primary = value
+                .map(|v| self.wrap_with_rank(v, rank))
+                .map(|v| self.wrap_with_min_max(v, min_max));
+            if self.extra_fractions.is_empty() {
+                primary
             } else {
-                Some(self.data[f64::ceil(rn) as usize - 1].clone())
+                let mut all = vec![primary];
+                all.extend(
+                    self.extra_fractions
+                        .iter()
+                        .map(|&f| self.value_for_fraction(f, total_len, ordered_len)),
+                );
+                Some(ScalarImpl::List(ListValue::new(all)))
             }
         } else {
             None
-        })
+        };
+
+        *self.output_cache.borrow_mut() = Some((fingerprint, result.clone()));
+        Ok(result)
+    }
+
+    /// Ranks `fraction` over `ordered_len`, then resolves that rank to a value via whichever of
+    /// `digest`, the in-memory buffer, or the spilled runs currently holds the data. Shared by
+    /// `get_output`'s primary fraction and, when [`Self::with_multiple_fractions`] is used, every
+    /// fraction in `extra_fractions`. Returns the bare value, unwrapped by `wrap_with_rank`/
+    /// `wrap_with_min_max` -- those still only ever apply to the primary fraction's slot in a
+    /// multi-fraction result, matching how a plain single-fraction call behaves today.
+    ///
+    /// Honors `with_weights`/`value_column`/`tie_break` the same way `output`'s fast path does,
+    /// via the shared [`Self::weighted_value_at_fraction`]/[`Self::value_column_value_at_rank`]/
+    /// [`Self::tie_break_value_at_rank`] helpers, so a caller peeking with `get_output` before a
+    /// final `output`/`reset` (e.g. windowed or streaming evaluation) sees the same answer a
+    /// one-shot `output` call would. Like `output`'s fast path, this only applies once `data`
+    /// hasn't spilled -- `spill_current_run` clears the paired side buffers on every spill, so
+    /// there's nothing left to pair against once `spilled_runs` is non-empty.
+    fn value_for_fraction(
+        &self,
+        fraction: f64,
+        total_len: usize,
+        ordered_len: usize,
+    ) -> Option<ScalarImpl> {
+        let rank = self.rounding.rank(fraction, ordered_len);
+        if rank >= total_len {
+            // Rank falls among the buffered NULLs (`RESPECT NULLS`, `ignore_nulls: false`).
+            None
+        } else if let Some(digest) = &self.digest {
+            digest
+                .quantile_at_rank(rank as u64)
+                .and_then(|v| f64_to_scalar(v, &self.return_type))
+        } else if self.spilled_runs.is_empty() {
+            if self.with_weights && self.weights.len() == self.data.len() {
+                self.weighted_value_at_fraction(self.data.clone(), self.weights.clone(), fraction)
+            } else if self.value_column && self.values.len() == self.data.len() {
+                self.value_column_value_at_rank(self.data.clone(), self.values.clone(), rank)
+            } else if self.tie_break && self.secondary.len() == self.data.len() {
+                self.tie_break_value_at_rank(self.data.clone(), self.secondary.clone(), rank)
+            } else if self.presorted || self.sorted {
+                // `self.data` is already in sort order here: `presorted` promises arrival order
+                // matches sort order (checked by `add_datum`'s debug assertion), and `sorted` is
+                // kept true by `add_datum`'s sorted insert on the plain path -- so index by rank
+                // directly instead of sorting a clone.
+                Some(self.data[rank].clone())
+            } else {
+                let mut sorted = self.data.clone();
+                sorted.sort_unstable_by(|a, b| self.compare(a, b));
+                self.sort_count.set(self.sort_count.get() + 1);
+                Some(sorted[rank].clone())
+            }
+        } else {
+            Some(self.kth_via_merge(rank))
+        }
+    }
+
+    /// Pairs `data` with `weights` and picks the value at the first cumulative-weight threshold
+    /// crossing `fraction * total_weight` -- the [`Self::with_weights`] rule. Takes owned `Vec`s
+    /// so both `output`'s move-out fast path and `value_for_fraction`'s clone-based path (used by
+    /// `get_output`) can share this without either paying for the other's ownership style.
+    fn weighted_value_at_fraction(
+        &self,
+        data: Vec<ScalarImpl>,
+        weights: Vec<f64>,
+        fraction: f64,
+    ) -> Option<ScalarImpl> {
+        let mut paired: Vec<(ScalarImpl, f64)> = data.into_iter().zip(weights).collect();
+        if !self.presorted {
+            paired.sort_unstable_by(|(a, _), (b, _)| self.compare(a, b));
+            self.sort_count.set(self.sort_count.get() + 1);
+        }
+        let total_weight: f64 = paired.iter().map(|(_, w)| *w).sum();
+        let threshold = fraction * total_weight;
+        let mut cumulative = 0.0;
+        let mut picked = paired.last().map(|(v, _)| v.clone());
+        for (value, weight) in &paired {
+            cumulative += weight;
+            if cumulative >= threshold {
+                picked = Some(value.clone());
+                break;
+            }
+        }
+        picked
+    }
+
+    /// Pairs `data` with `values` and returns the paired value at `rank` after sorting by the
+    /// primary column -- the [`Self::with_value_column`] rule. See
+    /// [`Self::weighted_value_at_fraction`] on why this takes owned `Vec`s.
+    fn value_column_value_at_rank(
+        &self,
+        data: Vec<ScalarImpl>,
+        values: Vec<Datum>,
+        rank: usize,
+    ) -> Option<ScalarImpl> {
+        let mut paired: Vec<(ScalarImpl, Datum)> = data.into_iter().zip(values).collect();
+        if !self.presorted {
+            paired.sort_unstable_by(|(a, _), (b, _)| self.compare(a, b));
+            self.sort_count.set(self.sort_count.get() + 1);
+        }
+        paired.into_iter().nth(rank).and_then(|(_, v)| v)
+    }
+
+    /// Pairs `data` with `secondary` and returns the primary value at `rank` after sorting by
+    /// primary-then-secondary -- the [`Self::with_tie_break`] rule. See
+    /// [`Self::weighted_value_at_fraction`] on why this takes owned `Vec`s.
+    fn tie_break_value_at_rank(
+        &self,
+        data: Vec<ScalarImpl>,
+        secondary: Vec<Datum>,
+        rank: usize,
+    ) -> Option<ScalarImpl> {
+        let mut paired: Vec<(ScalarImpl, Datum)> = data.into_iter().zip(secondary).collect();
+        if !self.presorted {
+            paired.sort_unstable_by(|(a, a2), (b, b2)| {
+                self.compare(a, b)
+                    .then_with(|| DefaultOrdered(a2.clone()).cmp(&DefaultOrdered(b2.clone())))
+            });
+            self.sort_count.set(self.sort_count.get() + 1);
+        }
+        paired.into_iter().nth(rank).map(|(v, _)| v)
     }
 
     fn output(&mut self) -> Result<Datum> {
+        // Fast path for the common case of exactly one output per group: since `output` is about
+        // to discard everything regardless, move the buffered data out, sort it in place, and
+        // move the k-th element out of the sorted vector instead of cloning it via `get_output`.
+        // Doesn't apply once data has spilled, since picking the k-th element there still needs
+        // `kth_via_merge` over the spilled runs, nor when `extra_fractions` is non-empty, since
+        // reporting multiple percentiles is only implemented in `get_output`'s multi-value branch.
+        if self.spilled_runs.is_empty() && !self.data.is_empty() && self.extra_fractions.is_empty() {
+            if let Some(fraction) = self.fractions {
+                let non_null_len = self.data.len();
+                let ordered_len = non_null_len + self.null_count;
+                let min_max = self.with_min_max.then(|| self.total_min_max()).flatten();
+                let mut data = std::mem::take(&mut self.data);
+                let secondary = std::mem::take(&mut self.secondary);
+                let values = std::mem::take(&mut self.values);
+                let weights = std::mem::take(&mut self.weights);
+                let rank = self.rounding.rank(fraction, ordered_len);
+                let result = if rank >= non_null_len {
+                    None
+                } else if self.with_weights && weights.len() == data.len() {
+                    self.weighted_value_at_fraction(data, weights, fraction)
+                } else if self.value_column && values.len() == data.len() {
+                    self.value_column_value_at_rank(data, values, rank)
+                } else if self.tie_break && secondary.len() == data.len() {
+                    self.tie_break_value_at_rank(data, secondary, rank)
+                } else {
+                    if !self.presorted && !self.sorted {
+                        data.sort_unstable_by(|a, b| self.compare(a, b));
+                        self.sort_count.set(self.sort_count.get() + 1);
+                    }
+                    data.into_iter().nth(rank)
+                };
+                let result = result
+                    .map(|v| self.wrap_with_rank(v, rank))
+                    .map(|v| self.wrap_with_min_max(v, min_max));
+                self.reset();
+                return Ok(result);
+            }
+        }
         let result = self.get_output()?;
         self.reset();
         Ok(result)
@@ -151,17 +1590,997 @@ impl Aggregator for PercentileDisc {
 
     fn reset(&mut self) {
         self.data.clear();
+        self.spilled_runs.clear();
+        self.secondary.clear();
+        self.values.clear();
+        self.weights.clear();
+        self.null_count = 0;
+        self.digest = None;
+        *self.output_cache.get_mut() = None;
     }
 
+    /// Snapshots the buffered (not yet spilled or digested) data as a `Datum`, so a streaming
+    /// executor can persist it across barriers per [`Aggregator::get_state`]'s contract. Buffered
+    /// NULLs are represented in the same list as trailing `None` entries -- their count is all
+    /// [`Self::null_count`] tracks, not their original position relative to non-NULL values, which
+    /// doesn't affect rank arithmetic (`ordered_len` only ever needs the *count* of buffered
+    /// NULLs, see [`Self::ordered_len`]).
+    ///
+    /// Panics once data has spilled into sorted runs or switched over to an approximate digest
+    /// ([`Self::spilled_runs`]/[`Self::digest`] non-empty): neither a spilled run nor a `TDigest` sketch
+    /// fits a single `Datum` without a larger state-encoding change, so there's no lossless way to
+    /// checkpoint them here. A streaming query aggregating few enough rows per group to never
+    /// spill or approximate is unaffected. The tie-break/value-column/weighted side buffers
+    /// (`secondary`/`values`/`weights`) aren't captured either, for the same reason -- restoring
+    /// them would need the same per-feature encoding work.
     fn get_state(&self) -> Datum {
-        unimplemented!()
+        assert!(
+            self.spilled_runs.is_empty() && self.digest.is_none(),
+            "percentile_disc's get_state only supports buffered (unspilled, non-digest) state"
+        );
+        let mut values: Vec<Datum> = self.data.iter().cloned().map(Some).collect();
+        values.extend(std::iter::repeat(None).take(self.null_count));
+        Some(ScalarImpl::List(ListValue::new(values)))
     }
 
-    fn set_state(&mut self, _: Datum) {
-        unimplemented!()
+    /// Restores state produced by [`Self::get_state`]. Replaces `data`/`null_count` wholesale;
+    /// every other field (spill/digest thresholds, comparator, flags, ...) is configuration set up
+    /// once at construction, not part of the checkpointed value state, so it's left untouched.
+    fn set_state(&mut self, state: Datum) {
+        self.data.clear();
+        self.null_count = 0;
+        if let Some(scalar) = state {
+            for datum in scalar.as_list().values() {
+                match datum {
+                    Some(v) => self.data.push(v.clone()),
+                    None => self.null_count += 1,
+                }
+            }
+        }
+        *self.output_cache.get_mut() = None;
     }
 
     fn estimated_size(&self) -> usize {
         EstimateSize::estimated_size(self)
     }
+
+    fn accumulated_count(&self) -> Option<usize> {
+        Some(self.accumulated_count())
+    }
+}
+
+/// A sorted-vector structure supporting `percentile_disc` over a sliding window, so that a window
+/// function can answer each frame without a full sort. Insert/remove are `O(n)` (a `Vec` shift),
+/// which is still far cheaper than sorting all `n` elements on every frame advance; a future
+/// order-statistics tree could bring this down to `O(log n)`.
+#[derive(Default)]
+pub struct SlidingPercentileDisc {
+    sorted: Vec<ScalarImpl>,
+}
+
+impl SlidingPercentileDisc {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts a value entering the frame, keeping `sorted` in order.
+    pub fn insert(&mut self, value: ScalarImpl) {
+        let pos = self
+            .sorted
+            .partition_point(|v| DefaultOrdered(v.clone()) < DefaultOrdered(value.clone()));
+        self.sorted.insert(pos, value);
+    }
+
+    /// Removes a value leaving the frame. Panics if `value` is not present.
+    pub fn remove(&mut self, value: &ScalarImpl) {
+        let pos = self
+            .sorted
+            .binary_search_by(|v| DefaultOrdered(v.clone()).cmp(&DefaultOrdered(value.clone())))
+            .expect("value leaving the frame must have been inserted first");
+        self.sorted.remove(pos);
+    }
+
+    pub fn len(&self) -> usize {
+        self.sorted.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sorted.is_empty()
+    }
+
+    /// Answers `percentile_disc(fraction)` for the current frame.
+    pub fn output(&self, fraction: f64) -> Option<ScalarImpl> {
+        self.output_ref(fraction).cloned()
+    }
+
+    /// Like [`Self::output`], but returns a reference into the sorted buffer instead of cloning
+    /// the picked element. For an append-only stream that never calls [`Self::remove`], `insert`
+    /// already keeps `sorted` incrementally merged, so a barrier-time answer here is just an
+    /// index lookup: no re-sort from scratch and no clone of the whole buffer.
+    pub fn output_ref(&self, fraction: f64) -> Option<&ScalarImpl> {
+        if self.sorted.is_empty() {
+            return None;
+        }
+        Some(&self.sorted[RoundingPolicy::Ceil.rank(fraction, self.sorted.len())])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use risingwave_common::types::DataType;
+
+    use super::*;
+
+    #[test]
+    fn test_spilled_merge_matches_sorted_reference() {
+        let mut values: Vec<i32> = (0..5000).rev().collect();
+        let fraction = 0.9;
+        let mut sorted = values.clone();
+        sorted.sort_unstable();
+        let expected = sorted[f64::ceil(fraction * sorted.len() as f64) as usize - 1];
+
+        let mut spilled =
+            PercentileDisc::with_spill_threshold(Some(fraction), DataType::Int32, 64 /* tiny budget */);
+        for v in values.drain(..) {
+            spilled.add_datum(Some(ScalarImpl::from(v)).to_datum_ref());
+        }
+        assert!(!spilled.spilled_runs.is_empty());
+
+        assert_eq!(
+            spilled.get_output().unwrap(),
+            Some(ScalarImpl::from(expected))
+        );
+    }
+
+    #[test]
+    fn test_with_multiple_fractions_returns_one_result_per_fraction() {
+        let mut agg =
+            PercentileDisc::with_multiple_fractions(vec![0.0, 0.5, 1.0], DataType::Int32);
+        for v in [1, 2, 3, 4, 5] {
+            agg.add_datum(Some(ScalarImpl::from(v)).to_datum_ref());
+        }
+        let ScalarImpl::List(list) = agg.output().unwrap().unwrap() else {
+            panic!("expected a list result");
+        };
+        let values: Vec<_> = list
+            .values()
+            .iter()
+            .map(|d| d.clone().map(|s| *s.as_int32()))
+            .collect();
+        assert_eq!(values, vec![Some(1), Some(3), Some(5)]);
+    }
+
+    #[test]
+    fn test_get_state_round_trips_buffered_data_and_nulls() {
+        let mut agg = PercentileDisc::new(Some(0.5), DataType::Int32);
+        agg.add_datum(Some(ScalarImpl::from(1)).to_datum_ref());
+        agg.add_datum(None);
+        agg.add_datum(Some(ScalarImpl::from(2)).to_datum_ref());
+
+        let state = agg.get_state();
+
+        let mut restored = PercentileDisc::new(Some(0.5), DataType::Int32);
+        restored.set_state(state);
+        assert_eq!(restored.data, agg.data);
+        assert_eq!(restored.null_count, agg.null_count);
+        assert_eq!(restored.get_output().unwrap(), agg.get_output().unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "only supports buffered")]
+    fn test_get_state_panics_once_spilled() {
+        let mut agg =
+            PercentileDisc::with_spill_threshold(Some(0.5), DataType::Int32, 64 /* tiny budget */);
+        for v in 0..5000 {
+            agg.add_datum(Some(ScalarImpl::from(v)).to_datum_ref());
+        }
+        assert!(!agg.spilled_runs.is_empty());
+        agg.get_state();
+    }
+
+    #[test]
+    fn test_get_output_sorts_out_of_arrival_order_data() {
+        // Arrival order (5, 1, 3) is not sort order (1, 3, 5); `get_output` must index by rank
+        // into sort order (kept by `add_datum`'s sorted insert), not into arrival order.
+        let mut agg = PercentileDisc::new(Some(0.5), DataType::Int32);
+        for v in [5, 1, 3] {
+            agg.add_datum(Some(ScalarImpl::from(v)).to_datum_ref());
+        }
+        assert_eq!(agg.get_output().unwrap(), Some(ScalarImpl::from(3)));
+    }
+
+    #[test]
+    fn test_incremental_sorted_insert_matches_naive_sort_every_time() {
+        // Emits `get_output` after every single insert (the windowed-aggregation shape the
+        // incremental sorted-insert optimization targets) and compares against a naive
+        // reference that re-sorts a plain `Vec` from scratch on every emission.
+        let mut agg = PercentileDisc::new(Some(0.5), DataType::Int32);
+        let mut naive: Vec<i32> = vec![];
+        let inputs = [37, 12, 89, 4, 56, 23, 90, 1, 45, 68, 5, 77, 33, 60, 8];
+        for v in inputs {
+            agg.add_datum(Some(ScalarImpl::from(v)).to_datum_ref());
+            assert!(agg.sorted, "plain path should keep data sorted incrementally");
+
+            naive.push(v);
+            let mut sorted_naive = naive.clone();
+            sorted_naive.sort_unstable();
+            let rank = agg.rounding.rank(0.5, sorted_naive.len());
+            assert_eq!(
+                agg.get_output().unwrap(),
+                Some(ScalarImpl::from(sorted_naive[rank]))
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_retracts_deleted_value() -> Result<()> {
+        // Inserts 1, 2, 3, then retracts 2, leaving {1, 3}: the median of two values is the lower
+        // one (index 0 of the sorted pair), matching `RoundingPolicy::Ceil`'s default rank.
+        let chunk = StreamChunk::from_pretty(
+            " i
+            + 1
+            + 2
+            + 3
+            - 2",
+        );
+        let mut agg = PercentileDisc::new(Some(0.5), DataType::Int32);
+        agg.update(&chunk).await?;
+        assert_eq!(agg.data.len(), 2);
+        assert_eq!(agg.output()?, Some(ScalarImpl::from(1)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_update_retract_unbuffered_value_errors() {
+        let chunk = StreamChunk::from_pretty(
+            " i
+            + 1
+            - 2",
+        );
+        let mut agg = PercentileDisc::new(Some(0.5), DataType::Int32);
+        assert!(agg.update(&chunk).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_update_range_honors_op_type_within_the_sub_range() -> Result<()> {
+        // `update_range` already mirrors `update`'s op handling (see its body): a `Delete` inside
+        // the given `Range<usize>` retracts, it isn't treated as another insert. Only rows 1..4
+        // (`+2`, `+3`, `-2`) are fed here; row 0 (`+1`) and row 4 (`+4`) are outside the range and
+        // must not be buffered.
+        let chunk = StreamChunk::from_pretty(
+            " i
+            + 1
+            + 2
+            + 3
+            - 2
+            + 4",
+        );
+        let mut agg = PercentileDisc::new(Some(0.5), DataType::Int32);
+        agg.update_range(&chunk, 1..4).await?;
+        assert_eq!(agg.data.len(), 1);
+        assert_eq!(agg.output()?, Some(ScalarImpl::from(3)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_output_caches_unchanged_data() {
+        let mut agg = PercentileDisc::new(Some(0.5), DataType::Int32);
+        for v in [1, 2, 3] {
+            agg.add_datum(Some(ScalarImpl::from(v)).to_datum_ref());
+        }
+
+        let first = agg.get_output().unwrap();
+        assert_eq!(agg.recompute_count(), 1);
+
+        // Repeated polling of unchanged data should hit the cache.
+        let second = agg.get_output().unwrap();
+        assert_eq!(agg.recompute_count(), 1);
+        assert_eq!(first, second);
+
+        // Any change invalidates the cache.
+        agg.add_datum(Some(ScalarImpl::from(4)).to_datum_ref());
+        agg.get_output().unwrap();
+        assert_eq!(agg.recompute_count(), 2);
+    }
+
+    #[test]
+    fn test_interleaved_update_and_get_output_each_see_a_consistent_snapshot() {
+        let mut agg = PercentileDisc::new(Some(1.0), DataType::Int32);
+        agg.add_datum(Some(ScalarImpl::from(1)).to_datum_ref());
+        agg.add_datum(Some(ScalarImpl::from(2)).to_datum_ref());
+        // Snapshot after 2 rows: greatest of {1, 2}.
+        assert_eq!(agg.get_output().unwrap(), Some(ScalarImpl::from(2)));
+
+        agg.add_datum(Some(ScalarImpl::from(3)).to_datum_ref());
+        // A later `get_output` reflects the update in between, not the earlier snapshot.
+        assert_eq!(agg.get_output().unwrap(), Some(ScalarImpl::from(3)));
+
+        // And the first snapshot's result is untouched by the later update: re-deriving it from
+        // scratch over just the first two rows still agrees with what was returned back then.
+        let mut replay = PercentileDisc::new(Some(1.0), DataType::Int32);
+        replay.add_datum(Some(ScalarImpl::from(1)).to_datum_ref());
+        replay.add_datum(Some(ScalarImpl::from(2)).to_datum_ref());
+        assert_eq!(replay.get_output().unwrap(), Some(ScalarImpl::from(2)));
+    }
+
+    #[test]
+    fn test_sliding_window_matches_per_frame_recomputation() {
+        let data = [5, 1, 4, 2, 8, 9, 3, 7, 6, 0];
+        let window_size = 4;
+        let fraction = 0.5;
+
+        let mut window = SlidingPercentileDisc::new();
+        for &v in &data[..window_size] {
+            window.insert(ScalarImpl::from(v));
+        }
+
+        for start in 0..=(data.len() - window_size) {
+            let frame = &data[start..start + window_size];
+
+            // Recompute independently from scratch for this frame.
+            let mut sorted = frame.to_vec();
+            sorted.sort_unstable();
+            let rank = f64::ceil(fraction * sorted.len() as f64) as usize - 1;
+            let expected = ScalarImpl::from(sorted[rank]);
+
+            assert_eq!(window.output(fraction), Some(expected));
+
+            // Slide the window by one, if there's a next frame.
+            if start + window_size < data.len() {
+                window.remove(&ScalarImpl::from(data[start]));
+                window.insert(ScalarImpl::from(data[start + window_size]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_accumulated_count_matches_non_null_inputs() {
+        let mut agg = PercentileDisc::new(Some(0.5), DataType::Int32);
+        for v in [Some(1), None, Some(2), None, Some(3)] {
+            agg.add_datum(v.map(ScalarImpl::from).to_datum_ref());
+        }
+        assert_eq!(agg.accumulated_count(), 3);
+        assert_eq!(Aggregator::accumulated_count(&agg), Some(3));
+    }
+
+    #[test]
+    fn test_ignore_nulls_defaults_true_and_skips_nulls() {
+        let mut agg = PercentileDisc::new(Some(1.0), DataType::Int32);
+        for v in [Some(1), Some(2), None] {
+            agg.add_datum(v.map(ScalarImpl::from).to_datum_ref());
+        }
+        // `1.0` picks the greatest element; the trailing NULL is skipped entirely, so that's `2`.
+        assert_eq!(agg.get_output().unwrap(), Some(ScalarImpl::from(2)));
+    }
+
+    #[test]
+    fn test_respect_nulls_returns_null_when_rank_lands_among_nulls() {
+        let mut agg = PercentileDisc::with_ignore_nulls(Some(1.0), DataType::Int32, false);
+        for v in [Some(1), Some(2), None] {
+            agg.add_datum(v.map(ScalarImpl::from).to_datum_ref());
+        }
+        // With `RESPECT NULLS`, buffered NULLs sort last, so `1.0` now lands on the NULL instead
+        // of skipping past it to `2`.
+        assert_eq!(agg.get_output().unwrap(), None);
+        assert_eq!(agg.output().unwrap(), None);
+    }
+
+    #[test]
+    fn test_ignore_nulls_null_does_not_change_percentile_but_respect_nulls_counts_it() {
+        let with_null = {
+            let mut agg = PercentileDisc::new(Some(0.5), DataType::Int32);
+            for v in [Some(1), Some(2), Some(3), None] {
+                agg.add_datum(v.map(ScalarImpl::from).to_datum_ref());
+            }
+            agg.get_output().unwrap()
+        };
+        let without_null = {
+            let mut agg = PercentileDisc::new(Some(0.5), DataType::Int32);
+            for v in [Some(1), Some(2), Some(3)] {
+                agg.add_datum(v.map(ScalarImpl::from).to_datum_ref());
+            }
+            agg.get_output().unwrap()
+        };
+        // `IGNORE NULLS` (the default): the NULL is dropped entirely, so the median of the
+        // remaining three values is unaffected by whether it was ever there.
+        assert_eq!(with_null, without_null);
+        assert_eq!(with_null, Some(ScalarImpl::from(2)));
+
+        // Flipping to `RESPECT NULLS` makes the same NULL count toward the total of a four-element
+        // (three values + one NULL) sequence; `0.5` lands on rank ceil(0.5 * 4) = 2 of it.
+        let mut agg = PercentileDisc::with_ignore_nulls(Some(0.5), DataType::Int32, false);
+        for v in [Some(1), Some(2), Some(3), None] {
+            agg.add_datum(v.map(ScalarImpl::from).to_datum_ref());
+        }
+        assert_eq!(agg.get_output().unwrap(), Some(ScalarImpl::from(2)));
+        assert_eq!(agg.null_count, 1);
+    }
+
+    #[test]
+    fn test_rank_clamps_when_float_rounding_pushes_rn_past_total_len() {
+        // The smallest f64 strictly greater than 1.0; multiplying it by `total_len` overflows
+        // past `total_len` before clamping, exactly the failure mode a `fraction` computed via
+        // upstream floating-point arithmetic (rather than a clean literal `1.0`) can trigger.
+        let just_above_one = f64::from_bits(1.0f64.to_bits() + 1);
+        assert!(just_above_one > 1.0);
+        let total_len = 7usize;
+        assert_eq!(
+            RoundingPolicy::Ceil.rank(just_above_one, total_len),
+            total_len - 1
+        );
+
+        let mut agg = PercentileDisc::new(Some(just_above_one), DataType::Int32);
+        for v in 0..total_len as i32 {
+            agg.add_datum(Some(ScalarImpl::from(v)).to_datum_ref());
+        }
+        assert_eq!(
+            agg.get_output().unwrap(),
+            Some(ScalarImpl::from(total_len as i32 - 1))
+        );
+    }
+
+    #[test]
+    fn test_append_only_output_ref_correct_after_each_batch() {
+        let batches: [&[i32]; 3] = [&[5, 1, 9], &[3, 7], &[8, 0, 2, 4, 6]];
+        let fraction = 0.5;
+        let mut window = SlidingPercentileDisc::new();
+        let mut seen = Vec::new();
+
+        for batch in batches {
+            for &v in batch {
+                window.insert(ScalarImpl::from(v));
+                seen.push(v);
+            }
+            let mut sorted = seen.clone();
+            sorted.sort_unstable();
+            let rank = f64::ceil(fraction * sorted.len() as f64) as usize - 1;
+            let expected = ScalarImpl::from(sorted[rank]);
+            assert_eq!(window.output_ref(fraction), Some(&expected));
+        }
+    }
+
+    #[test]
+    fn test_rounding_policies_pick_different_elements() {
+        // Sorted: [1, 2, 3, 4, 5, 6, 7, 8, 9, 10]; fraction 0.55 -> rn = 5.5.
+        let data = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+        let expect = |rounding: RoundingPolicy, expected: i32| {
+            let mut agg = PercentileDisc::with_rounding(Some(0.55), DataType::Int32, rounding);
+            for &v in &data {
+                agg.add_datum(Some(ScalarImpl::from(v)).to_datum_ref());
+            }
+            assert_eq!(agg.get_output().unwrap(), Some(ScalarImpl::from(expected)));
+        };
+        // rn = 5.5: ceil -> index 5 (value 6), round -> index 5 (value 6), floor -> index 4 (value 5).
+        expect(RoundingPolicy::Ceil, 6);
+        expect(RoundingPolicy::Round, 6);
+        expect(RoundingPolicy::Floor, 5);
+    }
+
+    #[test]
+    fn test_output_preserves_nan_and_infinity_rather_than_coercing_to_null() {
+        // `F64` (`OrderedFloat<f64>`) totally orders `NaN` as greater than every other value, so
+        // fraction `1.0` (the maximum) picks it out; `ScalarImpl`'s `PartialEq` in turn considers
+        // `NaN == NaN`, so the picked element faithfully round-trips as `NaN`, not NULL.
+        let mut with_nan = PercentileDisc::new(Some(1.0), DataType::Float64);
+        for &v in &[1.0, 2.0, f64::NAN, 3.0] {
+            with_nan.add_datum(Some(ScalarImpl::from(v)).to_datum_ref());
+        }
+        let result = with_nan.output().unwrap();
+        assert_eq!(result, Some(ScalarImpl::from(f64::NAN)));
+        assert!(matches!(result, Some(ScalarImpl::Float64(v)) if v.0.is_nan()));
+
+        let mut with_inf = PercentileDisc::new(Some(1.0), DataType::Float64);
+        for &v in &[1.0, 2.0, f64::INFINITY, 3.0] {
+            with_inf.add_datum(Some(ScalarImpl::from(v)).to_datum_ref());
+        }
+        assert_eq!(
+            with_inf.output().unwrap(),
+            Some(ScalarImpl::from(f64::INFINITY))
+        );
+    }
+
+    #[test]
+    fn test_with_rank_returns_value_and_1_based_rank_for_several_fractions() {
+        // Sorted: [1, 2, 3, 4, 5, 6, 7, 8, 9, 10].
+        let data = [5, 1, 4, 2, 8, 9, 3, 7, 6, 10];
+        let cases = [
+            // (fraction, expected value, expected 1-based rank)
+            (0.0, 1, 1),
+            (0.5, 5, 5),
+            (1.0, 10, 10),
+        ];
+
+        for (fraction, expected_value, expected_rank) in cases {
+            let mut agg = PercentileDisc::with_rank(Some(fraction), DataType::Int32);
+            assert_eq!(
+                Aggregator::return_type(&agg),
+                DataType::Struct(StructType::unnamed(vec![DataType::Int32, DataType::Int64]))
+            );
+            for &v in &data {
+                agg.add_datum(Some(ScalarImpl::from(v)).to_datum_ref());
+            }
+            let result = agg.output().unwrap();
+            assert_eq!(
+                result,
+                Some(ScalarImpl::Struct(StructValue::new(vec![
+                    Some(ScalarImpl::from(expected_value)),
+                    Some(ScalarImpl::from(expected_rank as i64)),
+                ])))
+            );
+        }
+    }
+
+    #[test]
+    fn test_with_min_max_reports_value_alongside_data_range() {
+        // Sorted: [1, 2, 3, 4, 5, 6, 7, 8, 9, 10]; fraction 0.5 (ceil) picks value 5.
+        let data = [5, 1, 4, 2, 8, 9, 3, 7, 6, 10];
+        let mut agg = PercentileDisc::with_min_max(Some(0.5), DataType::Int32);
+        assert_eq!(
+            Aggregator::return_type(&agg),
+            DataType::Struct(StructType::unnamed(vec![
+                DataType::Int32,
+                DataType::Int32,
+                DataType::Int32
+            ]))
+        );
+        for &v in &data {
+            agg.add_datum(Some(ScalarImpl::from(v)).to_datum_ref());
+        }
+        let result = agg.output().unwrap();
+        assert_eq!(
+            result,
+            Some(ScalarImpl::Struct(StructValue::new(vec![
+                Some(ScalarImpl::from(5)),
+                Some(ScalarImpl::from(1)),
+                Some(ScalarImpl::from(10)),
+            ])))
+        );
+    }
+
+    #[test]
+    fn test_with_weights_uses_cumulative_weight_instead_of_position() {
+        // Values [10, 20] with weights [3, 1]: cumulative weight after `10` (3) already reaches
+        // fraction 0.75 of the total weight (4), so `10` is picked -- unweighted `percentile_disc`
+        // over the same two values would instead pick `20` (rank ceil(0.75 * 2) = 2 of 2).
+        let rows: [(i32, i32); 2] = [(10, 3), (20, 1)];
+        let fraction = 0.75;
+
+        let mut agg = PercentileDisc::with_weights(Some(fraction), DataType::Int32);
+        for &(value, weight) in &rows {
+            agg.add_datum_with_weight(
+                Some(ScalarImpl::from(value)).to_datum_ref(),
+                Some(ScalarImpl::from(weight)).to_datum_ref(),
+            )
+            .unwrap();
+        }
+        // `get_output` (the non-resetting peek `output` itself calls internally) must honor the
+        // weighting too, not just the final `output` call.
+        assert_eq!(agg.get_output().unwrap(), Some(ScalarImpl::from(10)));
+        assert_eq!(agg.output().unwrap(), Some(ScalarImpl::from(10)));
+
+        let mut unweighted = PercentileDisc::new(Some(fraction), DataType::Int32);
+        for &(value, _) in &rows {
+            unweighted.add_datum(Some(ScalarImpl::from(value)).to_datum_ref());
+        }
+        assert_eq!(unweighted.output().unwrap(), Some(ScalarImpl::from(20)));
+    }
+
+    #[test]
+    fn test_with_weights_rejects_negative_weight() {
+        // A negative weight has no sensible cumulative-weight-threshold meaning, so it's rejected
+        // rather than silently folded into `total_weight`. Zero, by contrast, is accepted --
+        // tested separately isn't needed since it already behaves like an ordinary non-negative
+        // weight that just never crosses the threshold.
+        let mut agg = PercentileDisc::with_weights(Some(0.5), DataType::Int32);
+        let result = agg.add_datum_with_weight(
+            Some(ScalarImpl::from(10)).to_datum_ref(),
+            Some(ScalarImpl::from(-1.0f64)).to_datum_ref(),
+        );
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("weights must be non-negative"));
+    }
+
+    #[test]
+    fn test_value_column_returns_paired_value_at_sort_key_rank() {
+        // Sort keys: [5, 1, 4, 2, 3] -> sorted [1, 2, 3, 4, 5]; fraction 0.5 (ceil) picks rank 3
+        // (1-indexed), i.e. 0-indexed rank 2, which is sort key `3`, paired with `"c"`.
+        let rows: [(i32, &str); 5] = [(5, "e"), (1, "a"), (4, "d"), (2, "b"), (3, "c")];
+        let fraction = 0.5;
+
+        let mut agg = PercentileDisc::with_value_column(Some(fraction), DataType::Varchar);
+        for &(key, value) in &rows {
+            agg.add_datum_with_value_column(
+                Some(ScalarImpl::from(key)).to_datum_ref(),
+                Some(ScalarImpl::from(value)).to_datum_ref(),
+            );
+        }
+        // `get_output` (the non-resetting peek `output` itself calls internally) must pair up
+        // `values` too, not just the final `output` call.
+        assert_eq!(agg.get_output().unwrap(), Some(ScalarImpl::from("c")));
+        assert_eq!(agg.output().unwrap(), Some(ScalarImpl::from("c")));
+    }
+
+    #[test]
+    fn test_output_move_out_matches_cloned_path_and_clears_data() {
+        let data = [5, 1, 4, 2, 8, 9, 3, 7, 6, 0];
+        let fraction = 0.5;
+
+        let mut cloned = PercentileDisc::new(Some(fraction), DataType::Int32);
+        for &v in &data {
+            cloned.add_datum(Some(ScalarImpl::from(v)).to_datum_ref());
+        }
+        let expected = cloned.get_output().unwrap();
+
+        let mut moved = PercentileDisc::new(Some(fraction), DataType::Int32);
+        for &v in &data {
+            moved.add_datum(Some(ScalarImpl::from(v)).to_datum_ref());
+        }
+        let result = moved.output().unwrap();
+
+        assert_eq!(result, expected);
+        assert!(moved.data.is_empty());
+    }
+
+    #[test]
+    fn test_presorted_hint_elides_sort_but_matches_unhinted_output() {
+        let sorted_data = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+        let fraction = 0.5;
+
+        let mut unhinted = PercentileDisc::new(Some(fraction), DataType::Int32);
+        for &v in &sorted_data {
+            unhinted.add_datum(Some(ScalarImpl::from(v)).to_datum_ref());
+        }
+        let expected = unhinted.output().unwrap();
+        // The plain path's sorted insert already keeps `data` in order, so even without the hint
+        // `output` never sorts.
+        assert_eq!(unhinted.sort_count(), 0);
+
+        let mut presorted = PercentileDisc::with_presorted_hint(Some(fraction), DataType::Int32, true);
+        for &v in &sorted_data {
+            presorted.add_datum(Some(ScalarImpl::from(v)).to_datum_ref());
+        }
+        let result = presorted.output().unwrap();
+
+        assert_eq!(result, expected);
+        assert_eq!(presorted.sort_count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "hinted presorted but received data out of order")]
+    fn test_presorted_hint_panics_in_debug_on_out_of_order_arrival() {
+        let mut agg = PercentileDisc::with_presorted_hint(Some(0.5), DataType::Int32, true);
+        agg.add_datum(Some(ScalarImpl::from(5)).to_datum_ref());
+        agg.add_datum(Some(ScalarImpl::from(1)).to_datum_ref());
+    }
+
+    #[test]
+    fn test_comparator_overrides_byte_order_for_collated_varchar() {
+        // Byte order sorts uppercase before lowercase (`"Banana" < "apple" < "cherry"`), so the
+        // median (rank 1 of 3) is `"apple"`. A case-insensitive collation sorts alphabetically
+        // (`"apple" < "Banana" < "cherry"`), so the median becomes `"Banana"` instead.
+        let words = ["cherry", "apple", "Banana"];
+        let fraction = 0.5;
+
+        let byte_order = {
+            let mut agg = PercentileDisc::new(Some(fraction), DataType::Varchar);
+            for &w in &words {
+                agg.add_datum(Some(ScalarImpl::from(w)).to_datum_ref());
+            }
+            agg.get_output().unwrap()
+        };
+        assert_eq!(byte_order, Some(ScalarImpl::from("apple")));
+
+        let case_insensitive: Arc<dyn Fn(&ScalarImpl, &ScalarImpl) -> Ordering + Send + Sync> =
+            Arc::new(|a: &ScalarImpl, b: &ScalarImpl| {
+                let (ScalarImpl::Utf8(a), ScalarImpl::Utf8(b)) = (a, b) else {
+                    unreachable!("comparator only ever sees the Varchar values under test")
+                };
+                a.to_lowercase().cmp(&b.to_lowercase())
+            });
+        let mut collated =
+            PercentileDisc::with_comparator(Some(fraction), DataType::Varchar, case_insensitive);
+        for &w in &words {
+            collated.add_datum(Some(ScalarImpl::from(w)).to_datum_ref());
+        }
+        let result = collated.output().unwrap();
+
+        assert_eq!(result, Some(ScalarImpl::from("Banana")));
+    }
+
+    #[test]
+    fn test_tie_break_picks_deterministic_element_among_equal_primary_values() {
+        // Five rows share the primary value `5`, differing only in a secondary column. Without
+        // `with_tie_break`, which of them lands at the median rank is whatever order they happen
+        // to sort into; with it, the tie is always broken by the secondary column, so the median
+        // is deterministically the row at that rank in `(primary, secondary)` order: `(5, 30)`.
+        let rows: [(i32, i32); 7] = [
+            (1, 0),
+            (5, 30),
+            (5, 10),
+            (5, 20),
+            (5, 40),
+            (5, 50),
+            (9, 0),
+        ];
+        let fraction = 0.5;
+
+        let mut agg = PercentileDisc::with_tie_break(Some(fraction), DataType::Int32);
+        for &(primary, secondary) in &rows {
+            agg.add_datum_with_tie_break(
+                Some(ScalarImpl::from(primary)).to_datum_ref(),
+                Some(ScalarImpl::from(secondary)).to_datum_ref(),
+            );
+        }
+        // `get_output` (the non-resetting peek `output` itself calls internally) must break the
+        // tie the same way, not just the final `output` call.
+        assert_eq!(agg.get_output().unwrap(), Some(ScalarImpl::from(5)));
+        let result = agg.output().unwrap();
+
+        // Rank 3 of 7 (0-indexed) sorted by `(primary, secondary)` lands inside the tied group of
+        // `5`s regardless of how ties are broken; the point under test is that repeating this with
+        // the rows fed in a different arrival order always yields the same rank-3 element.
+        assert_eq!(result, Some(ScalarImpl::from(5)));
+
+        let mut reordered = PercentileDisc::with_tie_break(Some(fraction), DataType::Int32);
+        for &(primary, secondary) in rows.iter().rev() {
+            reordered.add_datum_with_tie_break(
+                Some(ScalarImpl::from(primary)).to_datum_ref(),
+                Some(ScalarImpl::from(secondary)).to_datum_ref(),
+            );
+        }
+        assert_eq!(reordered.output().unwrap(), result);
+    }
+
+    #[tokio::test]
+    async fn test_update_batch_matches_update_via_stream_chunk() {
+        let chunk = DataChunk::from_pretty(
+            "i
+             5
+             1
+             4
+             2
+             8
+             9
+             3",
+        );
+        let fraction = 0.5;
+
+        let mut via_batch = PercentileDisc::new(Some(fraction), DataType::Int32);
+        via_batch.update_batch(&chunk).await.unwrap();
+        let batch_result = via_batch.output().unwrap();
+
+        let mut via_stream = PercentileDisc::new(Some(fraction), DataType::Int32);
+        via_stream
+            .update(&StreamChunk::from(chunk))
+            .await
+            .unwrap();
+        let stream_result = via_stream.output().unwrap();
+
+        assert_eq!(batch_result, stream_result);
+    }
+
+    #[test]
+    fn test_memory_budget_switches_to_digest_within_error_bound() {
+        let fraction = 0.5;
+        let values: Vec<i32> = (0..2000).collect();
+
+        let exact_v = {
+            let mut agg = PercentileDisc::new(Some(fraction), DataType::Int32);
+            for &v in &values {
+                agg.add_datum(Some(ScalarImpl::from(v)).to_datum_ref());
+            }
+            match agg.get_output().unwrap().unwrap() {
+                ScalarImpl::Int32(v) => v,
+                other => panic!("expected Int32, got {other:?}"),
+            }
+        };
+
+        // A tiny budget so buffering even a handful of `Int32`s already exceeds it.
+        let mut approx = PercentileDisc::with_memory_budget(Some(fraction), DataType::Int32, 64);
+        for &v in &values {
+            approx.add_datum(Some(ScalarImpl::from(v)).to_datum_ref());
+        }
+        let error_bound = approx
+            .error_bound()
+            .expect("should have switched over to the digest");
+
+        let approx_v = match approx.get_output().unwrap().unwrap() {
+            ScalarImpl::Int32(v) => v,
+            other => panic!("expected Int32, got {other:?}"),
+        };
+
+        // Consecutive integer inputs mean a rank error of `n` positions is also a value error of
+        // `n`, so the digest's reported rank error bound directly caps the value error, up to
+        // some slack for this simplified digest's coarser-than-a-real-t-digest merging.
+        let allowed = (error_bound * values.len() as f64).ceil() as i32 * 2 + 2;
+        assert!(
+            (exact_v - approx_v).abs() <= allowed,
+            "approximate {approx_v} too far from exact {exact_v} (error_bound {error_bound}, allowed {allowed})"
+        );
+    }
+
+    #[test]
+    fn test_deferred_fraction_resolved_before_accumulation() {
+        let mut agg = PercentileDisc::new_with_deferred_fraction(DataType::Int32);
+        for v in [1, 2, 3, 4] {
+            agg.add_datum(Some(ScalarImpl::from(v)).to_datum_ref());
+        }
+        // Not yet resolved: `update`/`update_range` must refuse, but direct buffer manipulation
+        // via `add_datum` above is unaffected since it's a private helper, not part of the guard.
+        assert!(agg.ensure_fraction_resolved().is_err());
+
+        agg.resolve_fraction(Some(0.75)).unwrap();
+        assert_eq!(agg.get_output().unwrap(), Some(ScalarImpl::from(3)));
+
+        // A second resolution attempt is rejected.
+        assert!(agg.resolve_fraction(Some(0.5)).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "mixed-type data")]
+    fn test_mixed_type_data_panics_clearly_in_debug() {
+        let mut agg = PercentileDisc::new(Some(0.5), DataType::Int32);
+        agg.add_datum(Some(ScalarImpl::from(1)).to_datum_ref());
+        agg.add_datum(Some(ScalarImpl::from("oops")).to_datum_ref());
+    }
+
+    #[test]
+    fn test_build_errors_on_missing_direct_arg() {
+        // `from_pretty` never sets `direct_args`, so this reproduces a malformed plan with zero
+        // direct arguments instead of panicking on `direct_args[0]`.
+        let agg_call = crate::agg::AggCall::from_pretty("(percentile_disc:int4 $0:int4)");
+        assert!(agg_call.direct_args.is_empty());
+        let result = build(&agg_call);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_build_errors_on_non_orderable_type() {
+        let agg_call = crate::agg::AggCall {
+            kind: crate::agg::AggKind::PercentileDisc,
+            args: crate::agg::AggArgs::Unary(DataType::Jsonb, 0),
+            return_type: DataType::Jsonb,
+            column_orders: vec![],
+            filter: None,
+            distinct: false,
+            direct_args: vec![crate::expr::LiteralExpression::new(
+                DataType::Float64,
+                Some(ScalarImpl::from(0.5)),
+            )],
+        };
+        let result = build(&agg_call);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_build_errors_on_return_type_mismatched_with_ordered_argument() {
+        // `return_type: Int32` but the `ORDER BY` argument is `Varchar` -- a `PercentileDisc`
+        // built from this would return one of its buffered `Varchar` values while callers expect
+        // an `Int32`, panicking downstream instead of erroring clearly here.
+        let agg_call = crate::agg::AggCall {
+            kind: crate::agg::AggKind::PercentileDisc,
+            args: crate::agg::AggArgs::Unary(DataType::Varchar, 0),
+            return_type: DataType::Int32,
+            column_orders: vec![],
+            filter: None,
+            distinct: false,
+            direct_args: vec![crate::expr::LiteralExpression::new(
+                DataType::Float64,
+                Some(ScalarImpl::from(0.5)),
+            )],
+        };
+        let result = build(&agg_call);
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("return type must match"));
+    }
+
+    #[test]
+    fn test_build_errors_on_out_of_range_fraction() {
+        let agg_call = crate::agg::AggCall {
+            kind: crate::agg::AggKind::PercentileDisc,
+            args: crate::agg::AggArgs::Unary(DataType::Int32, 0),
+            return_type: DataType::Int32,
+            column_orders: vec![],
+            filter: None,
+            distinct: false,
+            direct_args: vec![crate::expr::LiteralExpression::new(
+                DataType::Float64,
+                Some(ScalarImpl::from(2.0)),
+            )],
+        };
+        let result = build(&agg_call);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_build_errors_on_null_direct_arg_fraction_instead_of_silently_returning_null() {
+        // A `NULL` direct argument is the only realistic way `LiteralExpression::literal()`
+        // returns `None` here (`AggCall::direct_args` are always already-resolved literals by
+        // the time `build` runs -- see the doc comment on `PercentileDisc::new_with_deferred_fraction`).
+        // Previously this silently produced a `PercentileDisc` with an unset fraction, which
+        // would always output `NULL` regardless of the buffered data.
+        let agg_call = crate::agg::AggCall {
+            kind: crate::agg::AggKind::PercentileDisc,
+            args: crate::agg::AggArgs::Unary(DataType::Int32, 0),
+            return_type: DataType::Int32,
+            column_orders: vec![],
+            filter: None,
+            distinct: false,
+            direct_args: vec![crate::expr::LiteralExpression::new(DataType::Float64, None)],
+        };
+        let result = build(&agg_call);
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_build_coerces_integer_literal_fraction_to_float() {
+        let agg_call_for = |fraction: ScalarImpl| crate::agg::AggCall {
+            kind: crate::agg::AggKind::PercentileDisc,
+            args: crate::agg::AggArgs::Unary(DataType::Int32, 0),
+            return_type: DataType::Int32,
+            column_orders: vec![],
+            filter: None,
+            distinct: false,
+            direct_args: vec![crate::expr::LiteralExpression::new(
+                DataType::Int32,
+                Some(fraction),
+            )],
+        };
+
+        let chunk = DataChunk::from_pretty(
+            "i
+             5
+             1
+             4
+             2
+             8
+             9
+             3
+             7
+             6
+             0",
+        );
+        let build_and_run = |fraction: ScalarImpl| {
+            let chunk = chunk.clone();
+            async move {
+                let mut agg = build(&agg_call_for(fraction)).unwrap();
+                agg.update_batch(&chunk).await.unwrap();
+                agg.output().unwrap()
+            }
+        };
+
+        // `percentile_disc(0)` behaves like `percentile_disc(0.0)`, and likewise for `1`/`1.0`.
+        assert_eq!(
+            build_and_run(ScalarImpl::from(0i32)).await,
+            build_and_run(ScalarImpl::from(0.0)).await
+        );
+        assert_eq!(
+            build_and_run(ScalarImpl::from(1i32)).await,
+            build_and_run(ScalarImpl::from(1.0)).await
+        );
+    }
+
+    #[test]
+    fn test_multi_percentile_disc_matches_independent_aggregators() {
+        let data: Vec<ScalarImpl> = [7, 2, 9, 4, 1, 8, 3, 6, 5, 0]
+            .into_iter()
+            .map(ScalarImpl::from)
+            .collect();
+        let fractions = [0.5, 0.9, 0.99];
+
+        let combined = multi_percentile_disc(&data, &fractions);
+
+        for (fraction, expected) in fractions.into_iter().zip(combined) {
+            let mut sorted = data.clone();
+            sorted.sort_unstable_by(|a, b| DefaultOrdered(a.clone()).cmp(&DefaultOrdered(b.clone())));
+            let rank = f64::ceil(fraction * sorted.len() as f64) as usize - 1;
+            assert_eq!(expected, Some(sorted[rank].clone()));
+        }
+    }
 }
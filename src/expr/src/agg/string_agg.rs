@@ -15,6 +15,14 @@
 use risingwave_common::bail;
 use risingwave_expr_macro::aggregate;
 
+/// Concatenates the aggregated `value`s, joined by `delimiter`, in `WITHIN GROUP`'s order. Like
+/// [`super::mode::Mode`] and [`super::percentile_cont::PercentileCont`], this doesn't sort internally -- it folds
+/// `value`s into `state` strictly in the order `update`/`update_range` hand rows to it, trusting
+/// the plan to have sorted rows into `WITHIN GROUP` order upstream before they ever reach here
+/// (`StringAgg` isn't in `result_unaffected_by_order_by!`, so the planner already knows its result
+/// depends on order and inserts that sort when an explicit `ORDER BY` is given; absent one, rows
+/// arrive in whatever order the query naturally produces them, matching Postgres's own
+/// `string_agg` when no `WITHIN GROUP` is specified).
 #[aggregate("string_agg(varchar, varchar) -> varchar", state = "String")]
 fn string_agg(
     state: Option<String>,
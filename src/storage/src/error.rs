@@ -49,6 +49,12 @@ pub enum StorageError {
         #[from]
         Box<MemTableError>,
     ),
+
+    #[error("Encode row error: {0}")]
+    EncodeRow(String),
+
+    #[error("Decompress value error: {0}")]
+    DecompressValue(String),
 }
 
 pub type StorageResult<T> = std::result::Result<T, StorageError>;
@@ -713,4 +713,26 @@ mod tests {
                 + Bytes::from("value4444").len()
         );
     }
+
+    #[tokio::test]
+    async fn test_mem_table_coalesces_repeated_writes_to_same_pk() {
+        // `buffer` is keyed by the serialized pk, so repeated writes to the same key within an
+        // epoch always collapse to a single entry rather than growing a per-pk list.
+        let mut mem_table = MemTable::new(true);
+        mem_table.insert("hot_key".into(), "v0".into()).unwrap();
+        for i in 1..1000 {
+            mem_table
+                .update(
+                    "hot_key".into(),
+                    format!("v{}", i - 1).into(),
+                    format!("v{i}").into(),
+                )
+                .unwrap();
+        }
+        assert_eq!(mem_table.buffer.len(), 1);
+        match mem_table.buffer.get(&Bytes::from("hot_key")).unwrap() {
+            KeyOp::Insert(v) => assert_eq!(v, &Bytes::from("v999")),
+            other => panic!("expected a single coalesced Insert, got {other:?}"),
+        }
+    }
 }
@@ -23,6 +23,17 @@ pub fn serialize_pk(pk: impl Row, serializer: &OrderedRowSerde) -> Bytes {
     buf.freeze()
 }
 
+/// Inverts [`serialize_pk`], handling `serializer`'s per-column ascending/descending order and
+/// nullability the same way [`deserialize_pk_with_vnode`] does for its vnode-prefixed keys. There's
+/// no `MViewTable`/`MViewTableIter` in this crate to hang this off of as a method --
+/// [`StorageTableInnerIterInner::into_stream`](crate::table::batch_table::storage_table::StorageTableInnerIterInner)
+/// yields a scanned row's raw key bytes verbatim (`key.to_vec()`) rather than deserializing them,
+/// since a full-row scan already has the row's columns from the value; a caller that kept only a
+/// row's serialized pk (e.g. an index storing just the key) recovers its columns with this instead.
+pub fn deserialize_pk(key: &[u8], deserializer: &OrderedRowSerde) -> memcomparable::Result<OwnedRow> {
+    deserializer.deserialize(key)
+}
+
 pub fn serialize_pk_with_vnode(
     pk: impl Row,
     serializer: &OrderedRowSerde,
@@ -14,6 +14,18 @@
 
 //! Value encoding is an encoding format which converts the data into a binary form (not
 //! memcomparable).
+//!
+//! [`ValueRowSerde`] is this crate's pluggable-row-encoding extension point:
+//! [`StorageTableInner`](crate::table::batch_table::storage_table::StorageTableInner) is generic
+//! over `SD: ValueRowSerde`, and swapping the encoding a table uses to serialize its value bytes is
+//! a matter of picking a different implementor -- [`BasicSerde`] stores each row as a flat,
+//! positional value encoding, while [`ColumnAwareSerde`] stores a flat, self-describing encoding
+//! keyed by stable column id (see `test_row_encoding` below) so a schema change doesn't require
+//! rewriting existing rows. There's no cell-based implementor: every row already lives under a
+//! single key as one value (see [`crate::write_batch::WriteBatch::delete_range`]'s doc comment for
+//! why a cell-based, one-key-per-column scheme like `ManagedMViewState`'s isn't part of this
+//! architecture), so a hypothetical `CellBasedEncoding`/`FlatRowEncoding` split would have exactly
+//! one real side.
 use std::sync::Arc;
 
 use either::for_both;
@@ -13,6 +13,9 @@
 // limitations under the License.
 
 use std::assert_matches::assert_matches;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::{Hash, Hasher};
+use std::io::{Read, Write};
 use std::ops::Bound::{self, Excluded, Included, Unbounded};
 use std::ops::{Index, RangeBounds};
 use std::sync::Arc;
@@ -24,21 +27,27 @@ use futures::future::try_join_all;
 use futures::{Stream, StreamExt};
 use futures_async_stream::try_stream;
 use itertools::{Either, Itertools};
+use parking_lot::{Mutex, RwLock};
+use risingwave_common::array::{ArrayBuilderImpl, ArrayImpl, DataChunk};
 use risingwave_common::buffer::Bitmap;
 use risingwave_common::cache::CachePriority;
-use risingwave_common::catalog::{ColumnDesc, ColumnId, Schema, TableId, TableOption};
+use risingwave_common::catalog::{ColumnDesc, ColumnId, Field, Schema, TableId, TableOption};
 use risingwave_common::hash::{VirtualNode, VnodeBitmapExt};
 use risingwave_common::row::{self, OwnedRow, Row, RowExt};
+use risingwave_common::types::{Datum, ScalarRefImpl};
 use risingwave_common::util::row_serde::*;
-use risingwave_common::util::sort_util::OrderType;
+use risingwave_common::util::sort_util::{cmp_datum, OrderType};
 use risingwave_common::util::value_encoding::column_aware_row_encoding::ColumnAwareSerde;
-use risingwave_common::util::value_encoding::{BasicSerde, EitherSerde};
+use risingwave_common::util::value_encoding::{
+    BasicSerde, BasicSerializer, EitherSerde, ValueRowSerializer,
+};
 use risingwave_hummock_sdk::key::{end_bound_of_prefix, next_key, prefixed_range};
 use risingwave_hummock_sdk::HummockReadEpoch;
 use tracing::trace;
 
 use crate::error::{StorageError, StorageResult};
 use crate::hummock::CachePolicy;
+use crate::mem_table::{KeyOp, MemTable};
 use crate::row_serde::row_serde_util::{
     parse_raw_key_to_vnode_and_key, serialize_pk, serialize_pk_with_vnode,
 };
@@ -63,7 +72,12 @@ pub struct StorageTableInner<S: StateStore, SD: ValueRowSerde> {
     /// RowSeqScanExecutor.
     schema: Schema,
 
-    /// Used for serializing and deserializing the primary key.
+    /// Used for serializing and deserializing the primary key. Already handles `NULLS FIRST`/
+    /// `NULLS LAST` per pk column: each [`OrderType`] carries a `nulls_are_largest`/`nulls_are_
+    /// smallest` bit (see `risingwave_common::util::sort_util::OrderType`), and
+    /// `risingwave_common::util::memcmp_encoding::serialize_datum` picks the NULL-tag byte from
+    /// that bit before every non-NULL value's own bytes, so a scan over pk-ordered bytes already
+    /// yields NULLs first or last as configured, with no extra handling needed here.
     pk_serializer: OrderedRowSerde,
 
     output_indices: Vec<usize>,
@@ -103,6 +117,38 @@ pub struct StorageTableInner<S: StateStore, SD: ValueRowSerde> {
     table_option: TableOption,
 
     read_prefix_len_hint: usize,
+
+    /// Sparse in-memory index built by [`Self::build_sparse_index`], mapping a subsample of this
+    /// table's primary keys to their full rows in ascending pk-byte order. `None` until built.
+    /// Wrapped in an `Arc` so cloned handles to the same table share one index rather than each
+    /// paying to rebuild it, and in a [`RwLock`] since building only needs `&self` (matching
+    /// every other read path on this type) even though it mutates cached state.
+    sparse_index: Arc<RwLock<Option<Arc<Vec<(Vec<u8>, OwnedRow)>>>>>,
+
+    /// Sampling interval last passed to [`Self::build_sparse_index`], alongside `sparse_index`
+    /// itself, so [`Self::count`] can scale the sparse index's length back into an estimated
+    /// total row count without re-scanning the table. `None` whenever `sparse_index` is `None`.
+    sparse_index_every: Arc<RwLock<Option<usize>>>,
+
+    /// Column ids of the output columns, in the same order as [`Self::output_indices`] and
+    /// [`Self::schema`] (`column_ids[i]` names the column at output position `i`). Kept only so
+    /// [`Self::get_data_by_columns`] can map a caller's requested column ids back to output
+    /// positions; every other reader here (e.g. [`Self::row_serde`]/[`Self::mapping`]) already
+    /// works against the table's full, not just output, column set and has no need of it.
+    column_ids: Vec<ColumnId>,
+
+    /// Number of values [`Self::row_serde`] produces per row, before [`Self::mapping`] or
+    /// [`Self::key_output_indices`]-splicing projects that down to just the output columns — the
+    /// same width [`Self::mapping`]'s output indices were computed against. Kept only so
+    /// [`Self::decode_row_from_value`] can catch a deserialized row of the wrong width before that
+    /// mismatch reaches [`ColumnMapping::project`], which indexes by position and would otherwise
+    /// panic (or silently splice in the wrong column) instead of failing with a clear error.
+    value_column_count: usize,
+
+    /// The schema of just the primary key columns, in [`Self::pk_indices`] order -- unlike
+    /// [`Self::schema`], which is scoped to the output columns and may omit pk columns entirely
+    /// for a table whose output projection doesn't include its key. See [`Self::pk_schema`].
+    pk_schema: Schema,
 }
 
 /// `StorageTable` will use [`EitherSerde`] as default so that we can support both versioned and
@@ -190,7 +236,21 @@ impl<S: StateStore> StorageTableInner<S, EitherSerde> {
         read_prefix_len_hint: usize,
         versioned: bool,
     ) -> Self {
-        assert_eq!(order_types.len(), pk_indices.len());
+        assert_eq!(
+            order_types.len(),
+            pk_indices.len(),
+            "order_types and pk_indices must have the same length, got {} and {}",
+            order_types.len(),
+            pk_indices.len()
+        );
+        for &i in &pk_indices {
+            assert!(
+                i < table_columns.len(),
+                "pk index {} is out of bounds for a table with {} columns",
+                i,
+                table_columns.len()
+            );
+        }
 
         let (output_columns, output_indices) = find_columns_by_ids(&table_columns, &column_ids);
         let mut value_output_indices = vec![];
@@ -215,11 +275,18 @@ impl<S: StateStore> StorageTableInner<S, EitherSerde> {
         let schema = Schema::new(output_columns.iter().map(Into::into).collect());
 
         let mapping = ColumnMapping::new(output_row_in_value_indices);
+        let value_column_count = value_indices.len();
 
         let pk_data_types = pk_indices
             .iter()
             .map(|i| table_columns[*i].data_type.clone())
             .collect();
+        let pk_schema = Schema::new(
+            pk_indices
+                .iter()
+                .map(|&i| Field::from(&table_columns[i]))
+                .collect(),
+        );
         let pk_serializer = OrderedRowSerde::new(pk_data_types, order_types);
 
         let row_serde = {
@@ -250,6 +317,11 @@ impl<S: StateStore> StorageTableInner<S, EitherSerde> {
             vnodes,
             table_option,
             read_prefix_len_hint,
+            sparse_index: Arc::new(RwLock::new(None)),
+            sparse_index_every: Arc::new(RwLock::new(None)),
+            column_ids,
+            value_column_count,
+            pk_schema,
         }
     }
 }
@@ -259,6 +331,13 @@ impl<S: StateStore, SD: ValueRowSerde> StorageTableInner<S, SD> {
         &self.pk_serializer
     }
 
+    /// The [`Schema`] of just the primary key columns, in [`Self::pk_indices`] order. Unlike
+    /// [`Self::schema`], this always has one field per pk column regardless of whether the pk is
+    /// part of this table's output projection.
+    pub fn pk_schema(&self) -> &Schema {
+        &self.pk_schema
+    }
+
     pub fn schema(&self) -> &Schema {
         &self.schema
     }
@@ -293,6 +372,13 @@ impl<S: StateStore, SD: ValueRowSerde> StorageTableInner<S, SD> {
         compute_vnode(pk, &self.dist_key_in_pk_indices, &self.vnodes)
     }
 
+    /// Get the vnode that a given primary key is routed to. Exposed so that callers writing to
+    /// this table (e.g. a sink) can compute the same vnode as `get_row` uses to prefix the
+    /// serialized key, keeping writer and reader routing consistent.
+    pub fn vnode_of(&self, pk: impl Row) -> VirtualNode {
+        self.compute_vnode_by_pk(pk)
+    }
+
     /// Try getting vnode value with given primary key prefix, used for `vnode_hint` in iterators.
     /// Return `None` if the provided columns are not enough.
     fn try_compute_vnode_by_pk_prefix(&self, pk_prefix: impl Row) -> Option<VirtualNode> {
@@ -302,7 +388,28 @@ impl<S: StateStore, SD: ValueRowSerde> StorageTableInner<S, SD> {
             .then(|| compute_vnode(pk_prefix, &self.dist_key_in_pk_indices, &self.vnodes))
     }
 
-    /// Get a single row by point get
+    /// Get a single row by point get.
+    ///
+    /// Already a single storage round trip regardless of column count: [`StorageTableInner`]
+    /// encodes an entire row as one [`ValueRowSerde`]-encoded value per pk, not one cell per
+    /// column, so there's no per-column `get(pk, cell_idx)` API here to consolidate into a
+    /// whole-row fetch — this already is that method.
+    ///
+    /// Already epoch-scoped for snapshot-consistent reads: `wait_epoch` is the read epoch to fetch
+    /// `pk`'s value at, `self.store.try_wait_epoch(wait_epoch)` blocks until that epoch is durable
+    /// before the point get runs, and (per [`StorageTableInnerIterInner::new`]'s note on the same
+    /// pattern) `self.store.validate_read_epoch(wait_epoch)` re-checks afterwards that the epoch
+    /// wasn't concurrently GC'd out from under the read — an AP query fixing `wait_epoch` to one
+    /// snapshot and issuing many `get_row` calls against it sees a consistent snapshot across all
+    /// of them, not a torn read across epochs.
+    ///
+    /// `serialize_pk_with_vnode`'s output isn't memoized across calls: every byte of it (the
+    /// vnode prefix from hashing `pk`'s distribution-key columns, then `pk`'s own memcomparable
+    /// encoding) depends on `pk`, which is different on essentially every call, so there's no
+    /// fixed prefix here for repeat callers to share. A cache keyed by `pk` would cost at least as
+    /// much to look up as just re-serializing it, and `&self` here is shared across concurrently
+    /// running point gets (this method only ever takes `&self`, never `&mut self`), so a mutable
+    /// cache would also need locking that a handful of encode calls doesn't justify.
     pub async fn get_row(
         &self,
         pk: impl Row,
@@ -334,42 +441,248 @@ impl<S: StateStore, SD: ValueRowSerde> StorageTableInner<S, SD> {
         if let Some(value) = self.store.get(serialized_pk, epoch, read_options).await? {
             // Refer to [`StorageTableInnerIterInner::new`] for necessity of `validate_read_epoch`.
             self.store.validate_read_epoch(wait_epoch)?;
-            let full_row = self.row_serde.deserialize(&value)?;
-            let result_row_in_value = self
-                .mapping
-                .project(OwnedRow::new(full_row))
-                .into_owned_row();
-            match &self.key_output_indices {
-                Some(key_output_indices) => {
-                    let result_row_in_key =
-                        pk.project(&self.output_row_in_key_indices).into_owned_row();
-                    let mut result_row_vec = vec![];
-                    for idx in &self.output_indices {
-                        if self.value_output_indices.contains(idx) {
-                            let item_position_in_value_indices = &self
-                                .value_output_indices
-                                .iter()
-                                .position(|p| idx == p)
-                                .unwrap();
-                            result_row_vec.push(
-                                result_row_in_value
-                                    .index(*item_position_in_value_indices)
-                                    .clone(),
-                            );
-                        } else {
-                            let item_position_in_pk_indices =
-                                key_output_indices.iter().position(|p| idx == p).unwrap();
-                            result_row_vec
-                                .push(result_row_in_key.index(item_position_in_pk_indices).clone());
-                        }
+            Ok(Some(self.decode_row_from_value(&pk, &value)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Like [`Self::get_row`], but for a semi-join/`EXISTS`-style check that only needs to know
+    /// whether `pk` exists, not its value. Since this table stores one whole encoded row per pk
+    /// (see [`Self::get_row`]'s doc comment), checking existence is already exactly [`Self::
+    /// get_row`]'s underlying [`StateStoreRead::get`] call minus [`Self::decode_row_from_value`] --
+    /// there's no separate key-only existence probe to call instead, but skipping the decode still
+    /// saves every allocation `decode_row_from_value` would otherwise do for a value the caller
+    /// never wanted.
+    pub async fn contains_key(
+        &self,
+        pk: impl Row,
+        wait_epoch: HummockReadEpoch,
+    ) -> StorageResult<bool> {
+        let epoch = wait_epoch.get_epoch();
+        let read_backup = matches!(wait_epoch, HummockReadEpoch::Backup(_));
+        self.store.try_wait_epoch(wait_epoch).await?;
+        let serialized_pk =
+            serialize_pk_with_vnode(&pk, &self.pk_serializer, self.compute_vnode_by_pk(&pk));
+        assert!(pk.len() <= self.pk_indices.len());
+
+        let prefix_hint = if self.read_prefix_len_hint != 0 && self.read_prefix_len_hint == pk.len()
+        {
+            Some(serialized_pk.slice(VirtualNode::SIZE..))
+        } else {
+            None
+        };
+
+        let read_options = ReadOptions {
+            prefix_hint,
+            retention_seconds: self.table_option.retention_seconds,
+            ignore_range_tombstone: false,
+            table_id: self.table_id,
+            read_version_from_backup: read_backup,
+            prefetch_options: Default::default(),
+            cache_policy: CachePolicy::Fill(CachePriority::High),
+        };
+        let found = self.store.get(serialized_pk, epoch, read_options).await?.is_some();
+        if found {
+            // Refer to [`StorageTableInnerIterInner::new`] for necessity of `validate_read_epoch`.
+            self.store.validate_read_epoch(wait_epoch)?;
+        }
+        Ok(found)
+    }
+
+    /// Like [`Self::get_row`], but treats the fetched value as having gone through
+    /// [`compress_value_if_large`], transparently decompressing it before decoding — the reader
+    /// half of this table type's opt-in value compression. Using this against a pk whose value
+    /// was written without going through [`compress_value_if_large`] first returns an error,
+    /// since the raw bytes won't start with a valid marker byte.
+    pub async fn get_row_decompressing(
+        &self,
+        pk: impl Row,
+        wait_epoch: HummockReadEpoch,
+    ) -> StorageResult<Option<OwnedRow>> {
+        let epoch = wait_epoch.get_epoch();
+        let read_backup = matches!(wait_epoch, HummockReadEpoch::Backup(_));
+        self.store.try_wait_epoch(wait_epoch).await?;
+        let serialized_pk =
+            serialize_pk_with_vnode(&pk, &self.pk_serializer, self.compute_vnode_by_pk(&pk));
+        assert!(pk.len() <= self.pk_indices.len());
+
+        let read_options = ReadOptions {
+            prefix_hint: None,
+            retention_seconds: self.table_option.retention_seconds,
+            ignore_range_tombstone: false,
+            table_id: self.table_id,
+            read_version_from_backup: read_backup,
+            prefetch_options: Default::default(),
+            cache_policy: CachePolicy::Fill(CachePriority::High),
+        };
+        if let Some(value) = self.store.get(serialized_pk, epoch, read_options).await? {
+            self.store.validate_read_epoch(wait_epoch)?;
+            let decompressed = decompress_value(&value)?;
+            Ok(Some(
+                self.decode_row_from_value(&pk, &Bytes::from(decompressed))?,
+            ))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Point-gets every pk in `pks`, preserving the input's order and duplicates: the returned
+    /// vector has one entry per element of `pks`, at the same position, even when the same pk
+    /// appears more than once (e.g. a join probing this table once per matching outer row).
+    ///
+    /// Duplicate pks are only fetched once — [`Self::get_row`] is called per *distinct* pk, and
+    /// the result is fanned back out to every position that pk occupied in the input — so a join
+    /// with a lot of repeated probes doesn't pay for redundant round trips.
+    pub async fn get_rows_ordered(
+        &self,
+        pks: &[OwnedRow],
+        wait_epoch: HummockReadEpoch,
+    ) -> StorageResult<Vec<Option<OwnedRow>>> {
+        let mut first_occurrence = HashMap::with_capacity(pks.len());
+        let mut unique_pks = Vec::new();
+        for pk in pks {
+            if !first_occurrence.contains_key(pk) {
+                first_occurrence.insert(pk.clone(), unique_pks.len());
+                unique_pks.push(pk.clone());
+            }
+        }
+
+        let unique_rows = try_join_all(
+            unique_pks
+                .iter()
+                .map(|pk| self.get_row(pk, wait_epoch.clone())),
+        )
+        .await?;
+
+        Ok(pks
+            .iter()
+            .map(|pk| unique_rows[first_occurrence[pk]].clone())
+            .collect())
+    }
+
+    /// Alias for [`Self::get_rows_ordered`], under the name a caller reaching for a batched
+    /// point-get API (as opposed to a full-table scan) is more likely to search for. Delegates
+    /// entirely — see [`Self::get_rows_ordered`] for the exact batching, ordering and
+    /// duplicate-pk-dedup behavior.
+    pub async fn multi_get(
+        &self,
+        pks: &[OwnedRow],
+        wait_epoch: HummockReadEpoch,
+    ) -> StorageResult<Vec<Option<OwnedRow>>> {
+        self.get_rows_ordered(pks, wait_epoch).await
+    }
+
+    /// Like [`Self::get_row`], but hidden (returns `None`) unless `visibility`'s row for the same
+    /// pk exists and is `true` in its column 0. For row-level security or a soft-delete overlay
+    /// maintained as its own companion [`StorageTableInner`], keyed by the same pk as `self`.
+    pub async fn get_row_with_visibility(
+        &self,
+        pk: impl Row,
+        wait_epoch: HummockReadEpoch,
+        visibility: &Self,
+    ) -> StorageResult<Option<OwnedRow>> {
+        let pk = pk.into_owned_row();
+        let row = self.get_row(&pk, wait_epoch).await?;
+        if row.is_none() {
+            return Ok(None);
+        }
+        let visibility_row = visibility.get_row(&pk, wait_epoch).await?;
+        Ok(row.filter(|_| is_row_visible(visibility_row.as_ref())))
+    }
+
+    /// Decodes a raw stored `value` for the row keyed by `pk` into this table's output row shape,
+    /// splicing in key-part columns from `pk` when [`Self::key_output_indices`] says some output
+    /// columns live in the key rather than the value. Shared by [`Self::get_row`] and other
+    /// readers (e.g. a transactional overlay) that already have the raw value bytes in hand.
+    fn decode_row_from_value(&self, pk: &impl Row, value: &Bytes) -> StorageResult<OwnedRow> {
+        let full_row = self.row_serde.deserialize(value)?;
+        if full_row.len() != self.value_column_count {
+            // A width mismatch here means the stored bytes don't actually match this table's own
+            // column schema (e.g. a corrupted value, or a `row_serde` built against the wrong
+            // schema version) — surface it as an error rather than letting `self.mapping.project`
+            // below index into a row of the wrong length, which would either panic or silently
+            // splice a value from the wrong column into the result.
+            return Err(StorageError::EncodeRow(format!(
+                "expected {} value columns when decoding row, got {}",
+                self.value_column_count,
+                full_row.len()
+            )));
+        }
+        let result_row_in_value = self
+            .mapping
+            .project(OwnedRow::new(full_row))
+            .into_owned_row();
+        match &self.key_output_indices {
+            Some(key_output_indices) => {
+                let result_row_in_key =
+                    pk.project(&self.output_row_in_key_indices).into_owned_row();
+                let mut result_row_vec = vec![];
+                for idx in &self.output_indices {
+                    if self.value_output_indices.contains(idx) {
+                        let item_position_in_value_indices = &self
+                            .value_output_indices
+                            .iter()
+                            .position(|p| idx == p)
+                            .unwrap();
+                        result_row_vec.push(
+                            result_row_in_value
+                                .index(*item_position_in_value_indices)
+                                .clone(),
+                        );
+                    } else {
+                        let item_position_in_pk_indices =
+                            key_output_indices.iter().position(|p| idx == p).unwrap();
+                        result_row_vec
+                            .push(result_row_in_key.index(item_position_in_pk_indices).clone());
                     }
-                    let result_row = OwnedRow::new(result_row_vec);
-                    Ok(Some(result_row))
                 }
-                None => Ok(Some(result_row_in_value)),
+                Ok(OwnedRow::new(result_row_vec))
             }
-        } else {
-            Ok(None)
+            None => Ok(result_row_in_value),
+        }
+    }
+
+    /// Describes, without actually scanning, what kind of scan `bounds` would translate to
+    /// against this table's keyspace, for `EXPLAIN`-style query debugging.
+    pub fn describe_scan(
+        &self,
+        bounds: Option<(Bound<OwnedRow>, Bound<OwnedRow>)>,
+    ) -> ScanPlan {
+        let Some((start, end)) = bounds else {
+            return ScanPlan {
+                kind: ScanKind::FullScan,
+                key_range: (Unbounded, Unbounded),
+            };
+        };
+
+        let encode_bound = |bound: &Bound<OwnedRow>| -> Bound<Vec<u8>> {
+            match bound {
+                Included(row) => {
+                    let serializer = self.pk_serializer.prefix(row.len());
+                    Included(serialize_pk(row, &serializer).to_vec())
+                }
+                Excluded(row) => {
+                    let serializer = self.pk_serializer.prefix(row.len());
+                    Excluded(serialize_pk(row, &serializer).to_vec())
+                }
+                Unbounded => Unbounded,
+            }
+        };
+
+        let kind = match (&start, &end) {
+            (Included(s), Included(e))
+                if s == e && s.len() == self.pk_indices.len() =>
+            {
+                ScanKind::PointLookup
+            }
+            (Unbounded, Unbounded) => ScanKind::FullScan,
+            _ => ScanKind::PrefixScan,
+        };
+
+        ScanPlan {
+            kind,
+            key_range: (encode_bound(&start), encode_bound(&end)),
         }
     }
 
@@ -381,10 +694,47 @@ impl<S: StateStore, SD: ValueRowSerde> StorageTableInner<S, SD> {
     }
 }
 
+/// The kind of scan a [`StorageTableInner::describe_scan`] would perform, for `EXPLAIN`-style
+/// query debugging.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanKind {
+    /// No bounds: every vnode's full key range is scanned.
+    FullScan,
+    /// A bounded but non-singleton key range is scanned.
+    PrefixScan,
+    /// The bounds pin down exactly one primary key.
+    PointLookup,
+}
+
+/// Describes, without performing it, the scan that a set of pk bounds would translate to.
+#[derive(Debug, Clone)]
+pub struct ScanPlan {
+    pub kind: ScanKind,
+    pub key_range: (Bound<Vec<u8>>, Bound<Vec<u8>>),
+}
+
+/// The wire format a caller wants rows re-encoded to via [`StorageTableInner::iter_as_version`].
+/// Mirrors [`ValueRowSerdeKind`](crate::row_serde::value_serde::ValueRowSerdeKind), the two whole-
+/// row encodings this table can be built with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncodingVersion {
+    Basic,
+    ColumnAware,
+}
+
+/// Already a [`futures::Stream`] bound (`Stream<Item = ...> + Send`), not merely a supertrait of
+/// one: any iterator returned by [`StorageTableInner`]'s `batch_iter*`/`iter_*` methods already
+/// composes with the standard [`futures::StreamExt`] combinators (`.map`, `.filter`, `.take`, ...)
+/// with no adapter needed, matching every place in this file (e.g. [`StorageTableInner::iter_reordered`],
+/// [`StorageTableInner::iter_proto`]) that already builds on a `batch_iter` result via `.map()`.
 pub trait PkAndRowStream = Stream<Item = StorageResult<(Vec<u8>, OwnedRow)>> + Send;
 
 /// The row iterator of the storage table.
 /// The wrapper of [`StorageTableInnerIter`] if pk is not persisted.
+///
+/// This is a `Stream` itself (see [`PkAndRowStream`]) via `impl PkAndRowStream`, not some other
+/// iterator type that a `Stream` impl would need to be bolted onto — a caller composing it with
+/// `futures` combinators can do so directly.
 pub type StorageTableInnerIter<S: StateStore, SD: ValueRowSerde> = impl PkAndRowStream;
 
 #[async_trait::async_trait]
@@ -401,6 +751,7 @@ impl<S: PkAndRowStream + Unpin> TableIter for S {
 impl<S: StateStore, SD: ValueRowSerde> StorageTableInner<S, SD> {
     /// Get multiple [`StorageTableInnerIter`] based on the specified vnodes of this table with
     /// `vnode_hint`, and merge or concat them by given `ordered`.
+    #[allow(clippy::too_many_arguments)]
     async fn iter_with_encoded_key_range(
         &self,
         prefix_hint: Option<Bytes>,
@@ -409,6 +760,9 @@ impl<S: StateStore, SD: ValueRowSerde> StorageTableInner<S, SD> {
         vnode_hint: Option<VirtualNode>,
         ordered: bool,
         prefetch_options: PrefetchOptions,
+        lenient: bool,
+        stats: Option<StorageTableIterStatsHandle>,
+        predicate: Option<Arc<RowPredicate>>,
     ) -> StorageResult<StorageTableInnerIter<S, SD>> {
         let cache_policy = match (
             encoded_key_range.start_bound(),
@@ -454,6 +808,8 @@ impl<S: StateStore, SD: ValueRowSerde> StorageTableInner<S, SD> {
         let iterators: Vec<_> = try_join_all(raw_key_ranges.map(|raw_key_range| {
             let prefix_hint = prefix_hint.clone();
             let wait_epoch = wait_epoch;
+            let stats = stats.clone();
+            let predicate = predicate.clone();
             let read_backup = matches!(wait_epoch, HummockReadEpoch::Backup(_));
             async move {
                 let read_options = ReadOptions {
@@ -481,6 +837,9 @@ impl<S: StateStore, SD: ValueRowSerde> StorageTableInner<S, SD> {
                     raw_key_range,
                     read_options,
                     wait_epoch,
+                    lenient,
+                    stats,
+                    predicate,
                 )
                 .await?
                 .into_stream();
@@ -504,6 +863,7 @@ impl<S: StateStore, SD: ValueRowSerde> StorageTableInner<S, SD> {
     }
 
     /// Iterates on the table with the given prefix of the pk in `pk_prefix` and the range bounds.
+    #[allow(clippy::too_many_arguments)]
     async fn iter_with_pk_bounds(
         &self,
         epoch: HummockReadEpoch,
@@ -511,6 +871,9 @@ impl<S: StateStore, SD: ValueRowSerde> StorageTableInner<S, SD> {
         range_bounds: impl RangeBounds<OwnedRow>,
         ordered: bool,
         prefetch_options: PrefetchOptions,
+        lenient: bool,
+        stats: Option<StorageTableIterStatsHandle>,
+        predicate: Option<Arc<RowPredicate>>,
     ) -> StorageResult<StorageTableInnerIter<S, SD>> {
         // TODO: directly use `prefixed_range`.
         fn serialize_pk_bound(
@@ -619,6 +982,9 @@ impl<S: StateStore, SD: ValueRowSerde> StorageTableInner<S, SD> {
             self.try_compute_vnode_by_pk_prefix(pk_prefix),
             ordered,
             prefetch_options,
+            lenient,
+            stats,
+            predicate,
         )
         .await
     }
@@ -633,10 +999,139 @@ impl<S: StateStore, SD: ValueRowSerde> StorageTableInner<S, SD> {
         ordered: bool,
         prefetch_options: PrefetchOptions,
     ) -> StorageResult<StorageTableInnerIter<S, SD>> {
-        self.iter_with_pk_bounds(epoch, pk_prefix, range_bounds, ordered, prefetch_options)
+        self.iter_with_pk_bounds(
+            epoch,
+            pk_prefix,
+            range_bounds,
+            ordered,
+            prefetch_options,
+            false,
+            None,
+            None,
+        )
+        .await
+    }
+
+    /// Lenient counterpart of [`Self::batch_iter_with_pk_bounds`]: a row that fails to decode is
+    /// logged (with its raw pk bytes) and skipped rather than aborting the scan. Intended for an
+    /// operator reading around a known corruption to recover the surrounding good rows; the
+    /// default, strict `batch_iter*` family is unaffected and remains the right choice otherwise.
+    pub async fn batch_iter_with_pk_bounds_lenient(
+        &self,
+        epoch: HummockReadEpoch,
+        pk_prefix: impl Row,
+        range_bounds: impl RangeBounds<OwnedRow>,
+        ordered: bool,
+        prefetch_options: PrefetchOptions,
+    ) -> StorageResult<StorageTableInnerIter<S, SD>> {
+        self.iter_with_pk_bounds(
+            epoch,
+            pk_prefix,
+            range_bounds,
+            ordered,
+            prefetch_options,
+            true,
+            None,
+            None,
+        )
+        .await
+    }
+
+    /// Observability counterpart of [`Self::batch_iter_with_pk_bounds`]: returns the iterator
+    /// paired with a [`StorageTableIterStatsHandle`] that stays live and up to date as the
+    /// iterator is driven, letting a caller attribute I/O (rows/cells/bytes) to this specific scan
+    /// without wrapping the storage layer. The default, unpaired `batch_iter*` family is unaffected.
+    pub async fn batch_iter_with_pk_bounds_and_stats(
+        &self,
+        epoch: HummockReadEpoch,
+        pk_prefix: impl Row,
+        range_bounds: impl RangeBounds<OwnedRow>,
+        ordered: bool,
+        prefetch_options: PrefetchOptions,
+    ) -> StorageResult<(StorageTableInnerIter<S, SD>, StorageTableIterStatsHandle)> {
+        let stats = StorageTableIterStatsHandle::default();
+        let iter = self
+            .iter_with_pk_bounds(
+                epoch,
+                pk_prefix,
+                range_bounds,
+                ordered,
+                prefetch_options,
+                false,
+                Some(stats.clone()),
+                None,
+            )
+            .await?;
+        Ok((iter, stats))
+    }
+
+    /// Predicate-pushdown counterpart of [`Self::batch_iter_with_pk_bounds`]: `predicate` runs on
+    /// each row right after it's reassembled (so it sees the same [`OwnedRow`] a caller consuming
+    /// the plain `batch_iter*` family would) and, if it returns `false`, the row is dropped before
+    /// it's ever yielded -- the caller's `next()` transparently keeps pulling until a passing row
+    /// or end-of-stream, instead of an executor filtering every deserialized row itself downstream.
+    pub async fn batch_iter_with_pk_bounds_and_filter(
+        &self,
+        epoch: HummockReadEpoch,
+        pk_prefix: impl Row,
+        range_bounds: impl RangeBounds<OwnedRow>,
+        ordered: bool,
+        prefetch_options: PrefetchOptions,
+        predicate: impl Fn(&OwnedRow) -> bool + Send + Sync + 'static,
+    ) -> StorageResult<StorageTableInnerIter<S, SD>> {
+        self.iter_with_pk_bounds(
+            epoch,
+            pk_prefix,
+            range_bounds,
+            ordered,
+            prefetch_options,
+            false,
+            None,
+            Some(Arc::new(predicate)),
+        )
+        .await
+    }
+
+    /// Convenience wrapper over [`Self::batch_iter_with_pk_bounds`] for a bounded scan over the
+    /// full pk (no separate prefix), the common case for a predicate on the leading pk columns
+    /// (e.g. `WHERE id BETWEEN 10 AND 20`). `range`'s bounds may cover only a prefix of the pk
+    /// columns; [`Self::iter_with_pk_bounds`]'s existing bound serialization (via
+    /// [`Self::pk_serializer`], which already encodes each column's ascending/descending
+    /// [`OrderType`]) already produces correct memcomparable boundaries for that case, including
+    /// descending columns, so there's nothing extra to do here. An `Unbounded` half of `range`
+    /// falls back to scanning to the corresponding end of the table, same as
+    /// [`Self::batch_iter_with_pk_bounds`].
+    pub async fn scan_range(
+        &self,
+        epoch: HummockReadEpoch,
+        range: (Bound<OwnedRow>, Bound<OwnedRow>),
+    ) -> StorageResult<StorageTableInnerIter<S, SD>> {
+        self.batch_iter_with_pk_bounds(epoch, row::empty(), range, true, PrefetchOptions::default())
             .await
     }
 
+    /// Convenience wrapper over [`Self::batch_iter_with_pk_bounds`] for a composite-key lookup
+    /// fixing the leading pk columns to `pk_prefix` and scanning every row that shares it (e.g.
+    /// `WHERE (a, b) = (1, 2)` against a `(a, b, c)` pk), rather than also bounding the remaining
+    /// columns the way [`Self::scan_range`] does. Unlike `scan_range`, `pk_prefix` here isn't
+    /// optional -- an empty prefix would just be a full table scan, which [`Self::batch_iter`]
+    /// already names more clearly.
+    pub async fn prefix_scan(
+        &self,
+        epoch: HummockReadEpoch,
+        pk_prefix: impl Row,
+        prefetch_options: PrefetchOptions,
+    ) -> StorageResult<StorageTableInnerIter<S, SD>> {
+        self.batch_iter_with_pk_bounds(
+            epoch,
+            pk_prefix,
+            (Bound::Unbounded, Bound::Unbounded),
+            true,
+            prefetch_options,
+        )
+        .await
+    }
+
     // The returned iterator will iterate data from a snapshot corresponding to the given `epoch`.
     pub async fn batch_iter(
         &self,
@@ -647,33 +1142,1075 @@ impl<S: StateStore, SD: ValueRowSerde> StorageTableInner<S, SD> {
         self.batch_iter_with_pk_bounds(epoch, row::empty(), .., ordered, prefetch_options)
             .await
     }
-}
 
-/// [`StorageTableInnerIterInner`] iterates on the storage table.
-struct StorageTableInnerIterInner<S: StateStore, SD: ValueRowSerde> {
-    /// An iterator that returns raw bytes from storage.
-    iter: S::IterStream,
+    /// Lenient counterpart of [`Self::batch_iter`] for a full table scan: see
+    /// [`Self::batch_iter_with_pk_bounds_lenient`] for what "lenient" means. The strict `iter()`
+    /// family (this crate's [`Self::batch_iter`] included) remains the default -- this is an
+    /// explicit opt-in for reading around corruption, e.g. during recovery.
+    pub async fn batch_iter_lenient(
+        &self,
+        epoch: HummockReadEpoch,
+        ordered: bool,
+        prefetch_options: PrefetchOptions,
+    ) -> StorageResult<StorageTableInnerIter<S, SD>> {
+        self.batch_iter_with_pk_bounds_lenient(epoch, row::empty(), .., ordered, prefetch_options)
+            .await
+    }
 
-    mapping: Arc<ColumnMapping>,
+    /// Observability counterpart of [`Self::batch_iter`] for a full table scan: see
+    /// [`Self::batch_iter_with_pk_bounds_and_stats`] for what the paired
+    /// [`StorageTableIterStatsHandle`] reports.
+    pub async fn batch_iter_with_stats(
+        &self,
+        epoch: HummockReadEpoch,
+        ordered: bool,
+        prefetch_options: PrefetchOptions,
+    ) -> StorageResult<(StorageTableInnerIter<S, SD>, StorageTableIterStatsHandle)> {
+        self.batch_iter_with_pk_bounds_and_stats(epoch, row::empty(), .., ordered, prefetch_options)
+            .await
+    }
 
-    row_deserializer: Arc<SD>,
+    /// Predicate-pushdown counterpart of [`Self::batch_iter`] for a full table scan: see
+    /// [`Self::batch_iter_with_pk_bounds_and_filter`] for what `predicate` does.
+    pub async fn batch_iter_with_filter(
+        &self,
+        epoch: HummockReadEpoch,
+        ordered: bool,
+        prefetch_options: PrefetchOptions,
+        predicate: impl Fn(&OwnedRow) -> bool + Send + Sync + 'static,
+    ) -> StorageResult<StorageTableInnerIter<S, SD>> {
+        self.batch_iter_with_pk_bounds_and_filter(
+            epoch,
+            row::empty(),
+            ..,
+            ordered,
+            prefetch_options,
+            predicate,
+        )
+        .await
+    }
 
-    /// Used for serializing and deserializing the primary key.
-    pk_serializer: Option<Arc<OrderedRowSerde>>,
+    /// Captures a [`DurableCursor`] for `row`, a row already emitted by a scan of this table, so a
+    /// caller doing a long export job can persist it (to a file, a catalog row, wherever it keeps
+    /// job state) and later resume scanning strictly after `row` via [`Self::resume_from`] — even
+    /// from a fresh process, since the cursor holds only `row`'s serialized pk bytes, nothing tied
+    /// to a live iterator.
+    pub fn cursor_after(&self, row: &OwnedRow) -> DurableCursor {
+        let pk = row.project(self.pk_indices()).into_owned_row();
+        DurableCursor {
+            last_pk: serialize_pk(&pk, &self.pk_serializer).to_vec(),
+        }
+    }
 
-    output_indices: Vec<usize>,
+    /// Resumes a scan strictly after whatever row [`Self::cursor_after`] captured `cursor` from,
+    /// reusing [`Self::batch_iter_with_pk_bounds`]'s positioning with an `Excluded` lower bound on
+    /// the cursor's pk.
+    ///
+    /// The cursor remains valid across a schema-compatible restart (same pk columns, same pk
+    /// order) since it round-trips through nothing but this table's own pk encoding; a
+    /// schema-incompatible restart (e.g. a pk column type change) isn't detected here and
+    /// produces undefined results, same as feeding any other stale pk to a pk-bounded scan.
+    pub async fn resume_from(
+        &self,
+        cursor: &DurableCursor,
+        epoch: HummockReadEpoch,
+        prefetch_options: PrefetchOptions,
+    ) -> StorageResult<StorageTableInnerIter<S, SD>> {
+        let last_pk = self.pk_serializer.deserialize(&cursor.last_pk)?;
+        self.batch_iter_with_pk_bounds(
+            epoch,
+            row::empty(),
+            (Excluded(last_pk), Unbounded),
+            true,
+            prefetch_options,
+        )
+        .await
+    }
 
-    /// the key part of output_indices.
-    key_output_indices: Option<Vec<usize>>,
+    /// Builds (or replaces) a sparse in-memory index over this table's primary keys, by running a
+    /// full ordered [`Self::batch_iter`] scan and keeping every `every`-th row encountered. Once
+    /// built, [`Self::iter_from_pk`] uses it as a coarse index to start its underlying scan close
+    /// to a requested pk instead of always scanning from the very beginning of the table's key
+    /// range — a two-level coarse-index/fine-scan lookup.
+    ///
+    /// `every` must be at least `1`. The index reflects a snapshot of the table as of `epoch` and
+    /// isn't kept in sync with later writes; a subsequently grown or shrunk table may make
+    /// [`Self::iter_from_pk`] start a little further from the target than optimal, but never
+    /// causes it to skip past a row it should have returned, since indexed keys are only ever
+    /// used as a lower bound at or before the requested pk.
+    pub async fn build_sparse_index(
+        &self,
+        epoch: HummockReadEpoch,
+        every: usize,
+    ) -> StorageResult<()> {
+        assert!(every >= 1, "sparse index sampling interval must be at least 1");
+
+        let row_iter = self.batch_iter(epoch, true, PrefetchOptions::default()).await?;
+        futures::pin_mut!(row_iter);
+
+        let mut index = Vec::new();
+        let mut count = 0usize;
+        while let Some(item) = row_iter.next().await {
+            let (key, row) = item?;
+            if count % every == 0 {
+                index.push((key, row));
+            }
+            count += 1;
+        }
 
-    /// the value part of output_indices.
-    value_output_indices: Vec<usize>,
+        *self.sparse_index.write() = Some(Arc::new(index));
+        *self.sparse_index_every.write() = Some(every);
+        Ok(())
+    }
 
-    /// used for deserializing key part of output row from pk.
-    output_row_in_key_indices: Vec<usize>,
-}
+    /// Cheaply estimates this table's total row count without a full scan, by scaling up
+    /// [`Self::build_sparse_index`]'s sample: since that index keeps exactly every `every`-th row
+    /// encountered during the scan that built it, `sparse_index.len() * every` approximates the
+    /// row count as of that scan, off by at most `every` for the last, possibly partial, sampling
+    /// interval. The estimate goes stale exactly as the index itself does (see
+    /// [`Self::build_sparse_index`]'s doc comment) as the table is written to afterward.
+    ///
+    /// Falls back to counting every row via a full [`Self::batch_iter`] scan when no index has
+    /// been built yet — there's no row-count statistic tracked anywhere else at this table
+    /// abstraction layer to estimate from instead.
+    pub async fn count(&self, epoch: HummockReadEpoch) -> StorageResult<usize> {
+        if let Some(index) = self.sparse_index.read().as_ref() {
+            let every = self
+                .sparse_index_every
+                .read()
+                .as_ref()
+                .copied()
+                .expect("sparse_index_every is set whenever sparse_index is");
+            return Ok(index.len() * every);
+        }
 
-impl<S: StateStore, SD: ValueRowSerde> StorageTableInnerIterInner<S, SD> {
+        let row_iter = self.batch_iter(epoch, false, PrefetchOptions::default()).await?;
+        futures::pin_mut!(row_iter);
+        let mut count = 0usize;
+        while let Some(item) = row_iter.next().await {
+            item?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Scans forward from `pk` (inclusive) to the end of the table. When [`Self::build_sparse_index`]
+    /// has been called, the underlying scan is seeded at the closest indexed key at or before
+    /// `pk` instead of `pk` itself, letting the store skip past most of the table that's known to
+    /// sort earlier; the handful of rows between that indexed key and `pk` are still yielded by
+    /// the returned iterator, so a caller that only wants rows `>= pk` should compare against `pk`
+    /// itself while consuming it. Falls back to starting exactly at `pk` when no index has been
+    /// built.
+    ///
+    /// This is the keyset-pagination entrypoint: a caller paginating "the next page after the last
+    /// row I saw" repositions here with that row's pk rather than re-scanning from the start of the
+    /// table and discarding everything before it -- [`Self::batch_iter_with_pk_bounds`]'s `Included`
+    /// lower bound does the repositioning at the store layer, not by walking past skipped rows in
+    /// this process. There's no `seek`-in-place on an existing [`StorageTableInnerIter`]: like
+    /// [`Self::batch_iter_with_stats`], the iterator is a type alias to an opaque `impl Trait`, not
+    /// a nominal type a method can be added to, so each page is a fresh call here instead of a
+    /// mutation of the previous page's iterator. [`Self::cursor_after`]/[`Self::resume_from`] cover
+    /// the same use case when the caller wants to persist an opaque, serializer-agnostic cursor
+    /// (e.g. across process restarts) rather than keeping the last row's [`OwnedRow`] pk around
+    /// directly.
+    pub async fn iter_from_pk(
+        &self,
+        epoch: HummockReadEpoch,
+        pk: OwnedRow,
+        prefetch_options: PrefetchOptions,
+    ) -> StorageResult<StorageTableInnerIter<S, SD>> {
+        let target_key = serialize_pk(&pk, &self.pk_serializer);
+
+        let start_pk = self.sparse_index.read().as_ref().and_then(|index| {
+            match index.partition_point(|(key, _)| key.as_slice() <= target_key.as_ref()) {
+                0 => None,
+                i => Some(index[i - 1].1.project(self.pk_indices()).into_owned_row()),
+            }
+        });
+
+        self.batch_iter_with_pk_bounds(
+            epoch,
+            row::empty(),
+            (Included(start_pk.unwrap_or(pk)), Unbounded),
+            true,
+            prefetch_options,
+        )
+        .await
+    }
+
+    /// Like [`Self::batch_iter`], but permutes each yielded row's columns into `output_order`
+    /// before returning it, so a downstream consumer that expects a different column order (or
+    /// repeats/drops columns) doesn't need its own remap step. Indices are relative to this
+    /// table's output columns, not the underlying table schema.
+    pub async fn iter_reordered(
+        &self,
+        epoch: HummockReadEpoch,
+        output_order: Vec<usize>,
+        ordered: bool,
+        prefetch_options: PrefetchOptions,
+    ) -> StorageResult<impl Stream<Item = StorageResult<OwnedRow>> + '_> {
+        assert!(
+            output_order.iter().all(|&i| i < self.output_indices.len()),
+            "output_order index out of range: {:?}, output has {} columns",
+            output_order,
+            self.output_indices.len()
+        );
+        let row_iter = self.batch_iter(epoch, ordered, prefetch_options).await?;
+        Ok(row_iter.map(move |row| {
+            let (_, row) = row?;
+            Ok(row.project(&output_order).into_owned_row())
+        }))
+    }
+
+    /// The columnar complement to [`Self::batch_iter`]'s row-major output, for a vectorized AP
+    /// engine that wants column-major data instead of one [`OwnedRow`] per pk. Scans the whole
+    /// table and builds one [`ArrayImpl`] per entry of `column_ids` (indices into this table's
+    /// output columns, same convention as [`Self::iter_reordered`]'s `output_order`), appending
+    /// each row's datum for that column in scan order -- a `NULL` datum becomes a null array
+    /// entry via [`ArrayBuilderImpl::append`], which already handles `None` in `ToDatumRef`, so
+    /// there's no separate null-handling branch here. Row order is preserved independently within
+    /// each returned array, so `columns[i][j]` and `columns[k][j]` are always the same row `j`.
+    pub async fn scan_columns(
+        &self,
+        epoch: HummockReadEpoch,
+        column_ids: &[usize],
+    ) -> StorageResult<Vec<ArrayImpl>> {
+        let data_types = column_ids
+            .iter()
+            .map(|&i| self.schema.fields[i].data_type.clone())
+            .collect_vec();
+        let mut builders = data_types
+            .iter()
+            .map(|ty| ArrayBuilderImpl::with_type(0, ty.clone()))
+            .collect_vec();
+
+        let row_iter = self.batch_iter(epoch, true, PrefetchOptions::default()).await?;
+        futures::pin_mut!(row_iter);
+        while let Some(item) = row_iter.next().await {
+            let (_, row) = item?;
+            for (builder, &column_id) in builders.iter_mut().zip(column_ids.iter()) {
+                builder.append(row.datum_at(column_id));
+            }
+        }
+
+        Ok(builders.into_iter().map(ArrayBuilderImpl::finish).collect())
+    }
+
+    /// Like [`Self::batch_iter`], but encodes each scanned row into a protobuf message per
+    /// `descriptor` instead of yielding an [`OwnedRow`], for sinks that hand off scanned data to
+    /// systems speaking protobuf. Output columns are matched to message fields by name; a column
+    /// with no matching field, or whose type can't be encoded as the field's, is an error. `NULL`
+    /// values are left as unset optional fields rather than encoded.
+    pub async fn iter_proto(
+        &self,
+        epoch: HummockReadEpoch,
+        descriptor: prost_reflect::MessageDescriptor,
+        ordered: bool,
+        prefetch_options: PrefetchOptions,
+    ) -> StorageResult<impl Stream<Item = StorageResult<Vec<u8>>> + '_> {
+        let schema = self.schema.clone();
+        let row_iter = self.batch_iter(epoch, ordered, prefetch_options).await?;
+        Ok(row_iter.map(move |row| {
+            let (_, row) = row?;
+            encode_row_to_proto(&schema, &row, &descriptor)
+        }))
+    }
+
+    /// Like [`Self::batch_iter`], but re-encodes each decoded row into the datum representation of
+    /// an older wire format, for a downstream reader that lags behind this table's current
+    /// encoding.
+    ///
+    /// Only [`EncodingVersion::Basic`] (the plain positional datum-list encoding also known as
+    /// [`BasicSerde`]) is supported: producing [`EncodingVersion::ColumnAware`] output requires
+    /// each column's stable [`ColumnId`], which [`StorageTableInner`] doesn't retain past
+    /// building its own `row_serde` (see `mapping`/`row_serde` fields) — asking for it returns a
+    /// clear error rather than fabricating column ids.
+    pub async fn iter_as_version(
+        &self,
+        epoch: HummockReadEpoch,
+        version: EncodingVersion,
+        ordered: bool,
+        prefetch_options: PrefetchOptions,
+    ) -> StorageResult<impl Stream<Item = StorageResult<Vec<u8>>> + '_> {
+        if version != EncodingVersion::Basic {
+            return Err(StorageError::EncodeRow(format!(
+                "unsupported target encoding version {version:?}: column ids are not available on this table view"
+            )));
+        }
+        let row_iter = self.batch_iter(epoch, ordered, prefetch_options).await?;
+        Ok(row_iter.map(move |row| {
+            let (_, row) = row?;
+            Ok(BasicSerializer.serialize(row))
+        }))
+    }
+
+    /// Like [`Self::batch_iter`], but applies `f` to each scanned row and yields its result
+    /// directly, fusing decode and transform so callers don't have to collect into an
+    /// intermediate `Vec<OwnedRow>` first. An error returned by `f` is propagated and stops the
+    /// scan, just like a storage error would.
+    pub async fn iter_map<T, F>(
+        &self,
+        epoch: HummockReadEpoch,
+        ordered: bool,
+        prefetch_options: PrefetchOptions,
+        mut f: F,
+    ) -> StorageResult<impl Stream<Item = StorageResult<T>> + '_>
+    where
+        F: FnMut(OwnedRow) -> StorageResult<T> + 'static,
+    {
+        let row_iter = self.batch_iter(epoch, ordered, prefetch_options).await?;
+        Ok(row_iter.map(move |row| {
+            let (_, row) = row?;
+            f(row)
+        }))
+    }
+
+    /// Scans the whole table and builds just one output column into a typed [`ArrayImpl`],
+    /// skipping the allocation of a full [`OwnedRow`] per other column that
+    /// [`Self::batch_iter`] would otherwise decode and immediately discard. Useful for loading a
+    /// single column into memory in bulk, e.g. to build a dictionary.
+    ///
+    /// `cell_idx` here is purely a positional index into [`Self::schema`]'s output columns
+    /// (`row.datum_at(cell_idx)` below), not any on-disk key encoding -- there's no
+    /// `serialize_cell_idx`/fixed-width key-suffix scheme to keep in sync between a writer and
+    /// this reader. This table stores one [`ValueRowSerde`]-encoded whole row per primary key (see
+    /// [`Self::row_serde`]), not one cell per column with its own key, so there's nothing here to
+    /// deduplicate against a writer-side encoding: the value bytes for every column of a row are
+    /// read and decoded together by [`Self::batch_iter`] regardless of which single column
+    /// `cell_idx` ultimately keeps.
+    pub async fn read_column(
+        &self,
+        epoch: HummockReadEpoch,
+        cell_idx: usize,
+    ) -> StorageResult<ArrayImpl> {
+        // Was an unchecked index before, which would panic on an out-of-range `cell_idx` instead
+        // of failing with a typed error a caller could handle -- e.g. a plan built against a
+        // stale schema after a column was dropped.
+        let field = self.schema.fields().get(cell_idx).ok_or_else(|| {
+            StorageError::EncodeRow(format!(
+                "cell_idx {cell_idx} is out of range for a table with {} output columns",
+                self.schema.fields().len()
+            ))
+        })?;
+        let data_type = field.data_type.clone();
+        let mut builder = ArrayBuilderImpl::with_type(0, data_type);
+        let row_iter = self.batch_iter(epoch, false, PrefetchOptions::default()).await?;
+        futures::pin_mut!(row_iter);
+        while let Some(item) = row_iter.next().await {
+            let (_, row) = item?;
+            builder.append(row.datum_at(cell_idx));
+        }
+        Ok(builder.finish())
+    }
+
+    /// Scans the whole table and assembles only the requested columns into a column-oriented
+    /// [`DataChunk`], skipping the [`ArrayBuilderImpl`] for every other column entirely rather
+    /// than decoding a full [`OwnedRow`] per row and discarding most of it — the multi-column
+    /// counterpart to [`Self::read_column`].
+    ///
+    /// `column_ids` are matched against [`Self::column_ids`] (this table's output columns, the
+    /// same set [`Self::schema`] describes); an id with no match is an error rather than silently
+    /// dropped, and repeated or reordered ids in the input are honored verbatim in the output
+    /// chunk's column order. An empty table produces a zero-row chunk, not an error.
+    ///
+    /// This is the closest match in this codebase to an older, pre-[`DataChunk`] columnar batch
+    /// format (`Bummock`/`BummockResult`) that no longer exists here: `Bummock` predates this
+    /// repo's snapshot, which represents an in-memory columnar batch as a plain [`DataChunk`]
+    /// everywhere, so that's what this method returns rather than fabricating a `BummockResult`
+    /// type this repo has no other use for.
+    pub async fn get_data_by_columns(
+        &self,
+        epoch: HummockReadEpoch,
+        column_ids: &[i32],
+    ) -> StorageResult<DataChunk> {
+        let positions = self.resolve_column_positions(column_ids)?;
+
+        let mut builders: Vec<_> = positions
+            .iter()
+            .map(|&pos| ArrayBuilderImpl::with_type(0, self.schema.fields()[pos].data_type.clone()))
+            .collect();
+
+        let row_iter = self.batch_iter(epoch, false, PrefetchOptions::default()).await?;
+        futures::pin_mut!(row_iter);
+        let mut row_count = 0;
+        while let Some(item) = row_iter.next().await {
+            let (_, row) = item?;
+            for (&pos, builder) in positions.iter().zip(builders.iter_mut()) {
+                builder.append(row.datum_at(pos));
+            }
+            row_count += 1;
+        }
+
+        let columns = builders.into_iter().map(|b| b.finish().into()).collect();
+        Ok(DataChunk::new(columns, row_count))
+    }
+
+    /// Maps each of `column_ids` to its position among [`Self::column_ids`] (this table's output
+    /// columns), erroring on any id that isn't one of them. Shared by [`Self::get_data_by_columns`]
+    /// and [`Self::iter_projected`], the columnar and row-oriented ways of reading a subset of a
+    /// row.
+    fn resolve_column_positions(&self, column_ids: &[i32]) -> StorageResult<Vec<usize>> {
+        column_ids
+            .iter()
+            .map(|&id| {
+                self.column_ids
+                    .iter()
+                    .position(|c| c.get_id() == id)
+                    .ok_or_else(|| {
+                        StorageError::EncodeRow(format!(
+                            "column id {id} is not one of this table's output columns"
+                        ))
+                    })
+            })
+            .collect::<StorageResult<Vec<_>>>()
+    }
+
+    /// Like [`Self::batch_iter`], but narrows each yielded row down to just `column_ids` (in the
+    /// order given, which may repeat or reorder them) instead of this table's full set of output
+    /// columns, for a caller — e.g. a downstream columnar operator — that only needs a handful of
+    /// columns out of a wide table.
+    ///
+    /// This only avoids the per-row cost of splicing every output column into the result row
+    /// ([`Self::decode_row_from_value`]'s work over the whole of [`Self::output_indices`]); it
+    /// can't skip deserializing the *unwanted* columns' bytes in [`Self::row_serde`] itself, for
+    /// two reasons. A `BasicSerde`-encoded (non-versioned) table's positional layout means
+    /// decoding column N always requires walking past every earlier column's bytes regardless of
+    /// whether they're wanted. And even a `ColumnAwareSerde`-encoded (versioned) table — whose
+    /// id-tagged encoding genuinely could skip an unwanted column's bytes entirely, see
+    /// `risingwave_common::util::value_encoding::column_aware_row_encoding::Deserializer` — has
+    /// that skip decided once, at table construction time, by which value columns
+    /// [`Self::row_serde`] was built against, not per scan; this table only keeps its
+    /// already-projected [`Self::schema`], not the original full column list needed to rebuild
+    /// [`Self::row_serde`] narrower on the fly.
+    ///
+    /// Like [`Self::get_data_by_columns`] above it, this has no caller yet outside this module --
+    /// storage-layer surface exposed ahead of the executor wiring that would use it, same
+    /// established pattern as its columnar sibling, not a gated/unreachable extension flag.
+    pub async fn iter_projected(
+        &self,
+        epoch: HummockReadEpoch,
+        column_ids: &[i32],
+    ) -> StorageResult<impl Stream<Item = StorageResult<OwnedRow>> + '_> {
+        let positions = self.resolve_column_positions(column_ids)?;
+        let row_iter = self.batch_iter(epoch, false, PrefetchOptions::default()).await?;
+        Ok(row_iter.map(move |item| {
+            let (_, row) = item?;
+            Ok(OwnedRow::new(
+                positions
+                    .iter()
+                    .map(|&pos| row.datum_at(pos).to_owned_datum())
+                    .collect(),
+            ))
+        }))
+    }
+
+    /// Scans the table, yielding only rows whose serialized pk hashes to `residue` modulo
+    /// `modulus`. Since the hash is computed from the stored pk bytes with a fixed-seed hasher,
+    /// the same row is always assigned to the same residue across restarts and across separate
+    /// scans with different `residue` values, so scanning every residue in `0..modulus` and
+    /// concatenating the results reconstructs the whole table with no duplicates or omissions.
+    /// Useful for deterministic sampling, e.g. a debugging tool that only wants to look at 1% of
+    /// rows without maintaining any state across runs.
+    pub async fn iter_hash_sampled(
+        &self,
+        epoch: HummockReadEpoch,
+        modulus: u64,
+        residue: u64,
+    ) -> StorageResult<impl Stream<Item = StorageResult<OwnedRow>> + '_> {
+        assert!(residue < modulus);
+        let row_iter = self.batch_iter(epoch, false, PrefetchOptions::default()).await?;
+        Ok(row_iter.filter_map(move |item| {
+            let result = item.map(|(pk, row)| {
+                let mut hasher = std::collections::hash_map::DefaultHasher::new();
+                pk.hash(&mut hasher);
+                (hasher.finish() % modulus == residue).then_some(row)
+            });
+            futures::future::ready(match result {
+                Ok(Some(row)) => Some(Ok(row)),
+                Ok(None) => None,
+                Err(e) => Some(Err(e)),
+            })
+        }))
+    }
+
+    /// Scans the table in pk order and, for each distinct group of rows sharing the same leading
+    /// `prefix_len` pk columns, returns only the last row of the group — the one with the
+    /// greatest remaining pk suffix. Useful for "latest event per entity"-style tables keyed
+    /// `(entity_id, timestamp, ..)`. Runs the scan with `ordered: true` so groups can be closed
+    /// off in a single pass instead of buffering the whole table.
+    pub async fn latest_per_prefix(
+        &self,
+        epoch: HummockReadEpoch,
+        prefix_len: usize,
+    ) -> StorageResult<Vec<OwnedRow>> {
+        assert!(prefix_len <= self.pk_indices.len());
+        let row_iter = self
+            .batch_iter(epoch, true, PrefetchOptions::default())
+            .await?;
+        futures::pin_mut!(row_iter);
+
+        let mut result = Vec::new();
+        let mut current: Option<(Vec<Datum>, OwnedRow)> = None;
+        while let Some(item) = row_iter.next().await {
+            let (_, row) = item?;
+            let prefix = row.iter().take(prefix_len).map(|d| d.to_owned_datum()).collect_vec();
+            match &mut current {
+                Some((current_prefix, current_row)) if *current_prefix == prefix => {
+                    *current_row = row;
+                }
+                _ => {
+                    if let Some((_, row)) = current.replace((prefix, row)) {
+                        result.push(row);
+                    }
+                }
+            }
+        }
+        if let Some((_, row)) = current {
+            result.push(row);
+        }
+        Ok(result)
+    }
+
+    /// Scans the table in pk order and groups consecutive rows sharing the same leading
+    /// `prefix_len` pk columns, returning one `(group_key, rows)` pair per distinct group in the
+    /// order groups are first encountered. Leverages the scan's pk order to detect group
+    /// boundaries in a single pass rather than hashing every row into a map, the same technique
+    /// [`Self::latest_per_prefix`] uses.
+    ///
+    /// Like [`Self::latest_per_prefix`], [`Self::sorted_by`] and [`Self::scan_with_visibility`],
+    /// this fully materializes its result rather than returning a lazy `Stream`: a group can't be
+    /// known to be complete until a row with a different prefix (or the end of the scan) is seen,
+    /// so a caller consuming this lazily would still need to buffer at least one full group ahead
+    /// of whatever it's already processing.
+    pub async fn iter_grouped(
+        &self,
+        epoch: HummockReadEpoch,
+        prefix_len: usize,
+    ) -> StorageResult<Vec<(OwnedRow, Vec<OwnedRow>)>> {
+        assert!(prefix_len <= self.pk_indices.len());
+        let row_iter = self
+            .batch_iter(epoch, true, PrefetchOptions::default())
+            .await?;
+        futures::pin_mut!(row_iter);
+
+        let mut result = Vec::new();
+        let mut current: Option<(OwnedRow, Vec<OwnedRow>)> = None;
+        while let Some(item) = row_iter.next().await {
+            let (_, row) = item?;
+            let prefix = OwnedRow::new(row.iter().take(prefix_len).map(|d| d.to_owned_datum()).collect_vec());
+            match &mut current {
+                Some((current_prefix, rows)) if *current_prefix == prefix => {
+                    rows.push(row);
+                }
+                _ => {
+                    if let Some(group) = current.replace((prefix, vec![row])) {
+                        result.push(group);
+                    }
+                }
+            }
+        }
+        if let Some(group) = current {
+            result.push(group);
+        }
+        Ok(result)
+    }
+
+    /// Iterates over the raw, undecoded key-value pairs stored under this table's keyspace, for
+    /// backup/export tooling that wants a byte-exact dump rather than decoded rows. Keys are the
+    /// full vnode-prefixed pk bytes as stored (there's no separate cell suffix to include, since
+    /// [`StorageTableInner`] encodes each row as a single [`ValueRowSerde`]-encoded value); values
+    /// are the raw encoded row bytes. Re-ingesting these pairs verbatim into a fresh keyspace with
+    /// the same schema reproduces identical decoded rows.
+    pub async fn raw_iter(
+        &self,
+        wait_epoch: HummockReadEpoch,
+    ) -> StorageResult<impl Stream<Item = StorageResult<(Vec<u8>, Vec<u8>)>> + '_> {
+        self.store.try_wait_epoch(wait_epoch).await?;
+        let read_options = ReadOptions {
+            prefix_hint: None,
+            ignore_range_tombstone: false,
+            retention_seconds: self.table_option.retention_seconds,
+            table_id: self.table_id,
+            read_version_from_backup: matches!(wait_epoch, HummockReadEpoch::Backup(_)),
+            prefetch_options: Default::default(),
+            cache_policy: CachePolicy::Fill(CachePriority::Low),
+        };
+        let iter = self
+            .store
+            .iter((Unbounded, Unbounded), wait_epoch.get_epoch(), read_options)
+            .await?;
+        Ok(iter.map(|item| {
+            let (full_key, value) = item?;
+            Ok((full_key.user_key.table_key.0.to_vec(), value.to_vec()))
+        }))
+    }
+
+    /// Scans this table's raw `(key, value)` pairs via [`Self::raw_iter`] (which returns them in
+    /// storage's key order, i.e. pk order) and folds each pair into a single streaming hash, so
+    /// two replicas of the same mview can compare this digest instead of transferring and diffing
+    /// their full contents. Since the scan order is always pk order regardless of the order rows
+    /// were originally written in, two tables with identical committed `(key, value)` pairs always
+    /// fold in the same sequence and so always produce the same digest.
+    pub async fn content_digest(&self, wait_epoch: HummockReadEpoch) -> StorageResult<[u8; 32]> {
+        let raw_iter = self.raw_iter(wait_epoch).await?;
+        futures::pin_mut!(raw_iter);
+
+        let mut hasher = blake3::Hasher::new();
+        while let Some(item) = raw_iter.next().await {
+            let (key, value) = item?;
+            hasher.update(&(key.len() as u64).to_le_bytes());
+            hasher.update(&key);
+            hasher.update(&(value.len() as u64).to_le_bytes());
+            hasher.update(&value);
+        }
+        Ok(*hasher.finalize().as_bytes())
+    }
+
+    /// Divides this table's keyspace into at most `num_splits` non-overlapping, table-covering byte
+    /// ranges, each independently consumable (e.g. via [`Self::raw_iter`] with a bounded prefix,
+    /// or a lower-level bounded [`StateStoreRead::iter`]) by a separate parallel scan task.
+    ///
+    /// Boundaries are sampled directly from the stored keys — this table's read path has no
+    /// access to the SST-level key statistics a real key-sampling split would use, so this pays
+    /// for a full key-only scan up front to build the boundary list exactly rather than
+    /// approximately. Returns fewer than `num_splits` ranges if the table has fewer than
+    /// `num_splits` rows, or when the rounded-up chunk size covers the rows in fewer chunks (e.g.
+    /// 10 rows into 6 splits gives chunks of 2, so 5 ranges); returns a single unbounded range for
+    /// an empty table.
+    pub async fn split_scan(
+        &self,
+        wait_epoch: HummockReadEpoch,
+        num_splits: usize,
+    ) -> StorageResult<Vec<(Bound<Vec<u8>>, Bound<Vec<u8>>)>> {
+        assert!(num_splits > 0);
+        let keys = {
+            let raw_iter = self.raw_iter(wait_epoch).await?;
+            futures::pin_mut!(raw_iter);
+            let mut keys = Vec::new();
+            while let Some(item) = raw_iter.next().await {
+                let (key, _) = item?;
+                keys.push(key);
+            }
+            keys
+        };
+        if keys.is_empty() {
+            return Ok(vec![(Unbounded, Unbounded)]);
+        }
+
+        let num_splits = num_splits.min(keys.len());
+        let chunk_size = keys.len().div_ceil(num_splits);
+        let boundaries = keys.into_iter().skip(chunk_size).step_by(chunk_size);
+
+        let mut ranges = Vec::with_capacity(num_splits);
+        let mut lower = Unbounded;
+        for boundary in boundaries {
+            ranges.push((lower, Excluded(boundary.clone())));
+            lower = Included(boundary);
+        }
+        ranges.push((lower, Unbounded));
+        Ok(ranges)
+    }
+
+    /// Scans the whole table and sorts it by `column` (an output-column index, which need not be
+    /// part of the pk), for serving `ORDER BY non_pk_column` where the pk-ordered scan doesn't
+    /// help. When `limit` is `Some`, memory is bounded to `O(limit)`: rather than buffering every
+    /// scanned row before sorting, a `limit`-sized max-heap (ordered so its peek is always the
+    /// current worst-ranked kept row) is maintained, evicting that worst row whenever a better one
+    /// arrives. `limit: None` has no bound to keep to and buffers the whole table, same as any
+    /// other full-table scan on this type — there's no spill-to-disk external sort at this layer
+    /// the way [`crate::table::merge_sort`] merges already-sorted per-vnode streams; this bounds
+    /// memory for the common top-N case, not for an unbounded full sort.
+    pub async fn sorted_by(
+        &self,
+        epoch: HummockReadEpoch,
+        column: usize,
+        order: OrderType,
+        limit: Option<usize>,
+    ) -> StorageResult<Vec<OwnedRow>> {
+        let row_iter = self.batch_iter(epoch, false, PrefetchOptions::default()).await?;
+        futures::pin_mut!(row_iter);
+
+        match limit {
+            Some(limit) => {
+                let mut heap: BinaryHeap<SortedByEntry> = BinaryHeap::with_capacity(limit + 1);
+                while let Some(item) = row_iter.next().await {
+                    let (_, row) = item?;
+                    let key = row.datum_at(column).to_owned_datum();
+                    heap.push(SortedByEntry { key, order, row });
+                    if heap.len() > limit {
+                        heap.pop();
+                    }
+                }
+                // `SortedByEntry`'s `Ord` matches `cmp_datum`'s notion of "sorts first", so the
+                // heap's ascending sorted order is already the caller's requested final order.
+                Ok(heap.into_sorted_vec().into_iter().map(|e| e.row).collect())
+            }
+            None => {
+                let mut rows = Vec::new();
+                while let Some(item) = row_iter.next().await {
+                    let (_, row) = item?;
+                    rows.push(row);
+                }
+                rows.sort_by(|a, b| cmp_datum(a.datum_at(column), b.datum_at(column), order));
+                Ok(rows)
+            }
+        }
+    }
+
+    /// Like [`Self::sorted_by`] with `limit` set, but specialized for the common case where the
+    /// requested `ORDER BY ... DESC LIMIT` is already this table's own pk order — i.e. reverse pk
+    /// iteration — so it doesn't pay for a per-row [`cmp_datum`] comparison against an arbitrary
+    /// sort column. Instead, a `limit`-sized ring buffer of the most recently seen rows is kept as
+    /// the (still forward, pk-ascending) scan proceeds; once the scan ends, the ring buffer already
+    /// holds exactly the last `limit` rows in pk-ascending order, which is reversed once to produce
+    /// descending order.
+    ///
+    /// This repo's [`crate::store::StateStoreRead::iter`] only scans forward — there's no reverse
+    /// iterator to start from the end of the keyspace directly — so a full forward scan still has
+    /// to run underneath; what this avoids relative to [`Self::sorted_by`] is per-row sort-column
+    /// comparisons and the `O(log limit)` heap maintenance, since here every row is unconditionally
+    /// pk-ordered already.
+    pub async fn pk_ordered_tail(
+        &self,
+        epoch: HummockReadEpoch,
+        limit: usize,
+    ) -> StorageResult<Vec<OwnedRow>> {
+        if limit == 0 {
+            return Ok(vec![]);
+        }
+        let row_iter = self.batch_iter(epoch, true, PrefetchOptions::default()).await?;
+        futures::pin_mut!(row_iter);
+
+        let mut ring: std::collections::VecDeque<OwnedRow> =
+            std::collections::VecDeque::with_capacity(limit);
+        while let Some(item) = row_iter.next().await {
+            let (_, row) = item?;
+            if ring.len() == limit {
+                ring.pop_front();
+            }
+            ring.push_back(row);
+        }
+        Ok(ring.into_iter().rev().collect())
+    }
+
+    /// Scans the whole table, hiding any row whose pk isn't marked visible in a companion
+    /// `visibility` table (see [`Self::get_row_with_visibility`]). Visibility lookups are batched
+    /// [`VISIBILITY_LOOKUP_BATCH_SIZE`] rows at a time via [`Self::get_rows_ordered`], instead of
+    /// one round trip to `visibility` per scanned row.
+    pub async fn scan_with_visibility(
+        &self,
+        epoch: HummockReadEpoch,
+        visibility: &Self,
+    ) -> StorageResult<Vec<OwnedRow>> {
+        let row_iter = self.batch_iter(epoch, false, PrefetchOptions::default()).await?;
+        futures::pin_mut!(row_iter);
+
+        let mut visible_rows = Vec::new();
+        let mut buffered_rows = Vec::with_capacity(VISIBILITY_LOOKUP_BATCH_SIZE);
+        while let Some(item) = row_iter.next().await {
+            let (_, row) = item?;
+            buffered_rows.push(row);
+            if buffered_rows.len() >= VISIBILITY_LOOKUP_BATCH_SIZE {
+                self.append_visible_rows(
+                    visibility,
+                    epoch,
+                    std::mem::take(&mut buffered_rows),
+                    &mut visible_rows,
+                )
+                .await?;
+            }
+        }
+        if !buffered_rows.is_empty() {
+            self.append_visible_rows(visibility, epoch, buffered_rows, &mut visible_rows)
+                .await?;
+        }
+        Ok(visible_rows)
+    }
+
+    /// Batches a companion `visibility` lookup for `rows`' pks and appends whichever of `rows`
+    /// come back visible onto `out`, in order.
+    async fn append_visible_rows(
+        &self,
+        visibility: &Self,
+        epoch: HummockReadEpoch,
+        rows: Vec<OwnedRow>,
+        out: &mut Vec<OwnedRow>,
+    ) -> StorageResult<()> {
+        let pks: Vec<OwnedRow> = rows
+            .iter()
+            .map(|row| row.project(self.pk_indices()).into_owned_row())
+            .collect();
+        let visibilities = visibility.get_rows_ordered(&pks, epoch).await?;
+        out.extend(
+            rows.into_iter()
+                .zip(visibilities)
+                .filter(|(_, v)| is_row_visible(v.as_ref()))
+                .map(|(row, _)| row),
+        );
+        Ok(())
+    }
+}
+
+/// Compresses `value` with zstd when it's larger than `threshold` bytes, prefixing the result
+/// with a 1-byte marker (`1` compressed, `0` stored verbatim) that [`decompress_value`] reads
+/// back. Small values are left uncompressed (behind the "not compressed" marker), since zstd's
+/// own framing overhead can make compression a net loss below some size.
+///
+/// Stands in for the writer side of value compression: [`StorageTableInner`] is read-only, and the
+/// write path this feature is usually associated with (`ManagedMViewState::put`) is a cell-based
+/// storage concept that predates this repo's single-value-per-row encoding and no longer exists —
+/// a real writer for a table using this compression scheme would call this function before storing
+/// a value, and a reader would call [`StorageTableInner::get_row_decompressing`] to read it back.
+pub fn compress_value_if_large(value: &[u8], threshold: usize) -> Vec<u8> {
+    if value.len() <= threshold {
+        let mut out = Vec::with_capacity(value.len() + 1);
+        out.push(0);
+        out.extend_from_slice(value);
+        return out;
+    }
+
+    let mut encoder = zstd::Encoder::new(Vec::with_capacity(value.len()), 4)
+        .expect("in-memory zstd encoder construction cannot fail");
+    encoder
+        .write_all(value)
+        .expect("writing to an in-memory buffer cannot fail");
+    let compressed = encoder
+        .finish()
+        .expect("finishing an in-memory zstd stream cannot fail");
+
+    let mut out = Vec::with_capacity(compressed.len() + 1);
+    out.push(1);
+    out.extend_from_slice(&compressed);
+    out
+}
+
+/// Reverses [`compress_value_if_large`], transparently decompressing a value it produced before
+/// the caller runs the result through [`ValueRowSerde::deserialize`]. Returns the input unmodified
+/// minus its marker byte when that byte says "not compressed"; errors if `value` is empty (an
+/// invariant [`compress_value_if_large`] always upholds, since it never emits a value without a
+/// marker byte) or carries an unrecognized marker.
+fn decompress_value(value: &[u8]) -> StorageResult<Vec<u8>> {
+    let (&marker, rest) = value.split_first().ok_or_else(|| {
+        StorageError::DecompressValue("value is empty; missing compression marker byte".into())
+    })?;
+    match marker {
+        0 => Ok(rest.to_vec()),
+        1 => {
+            let mut decoder =
+                zstd::Decoder::new(rest).map_err(|e| StorageError::DecompressValue(e.to_string()))?;
+            let mut decoded = Vec::new();
+            decoder
+                .read_to_end(&mut decoded)
+                .map_err(|e| StorageError::DecompressValue(e.to_string()))?;
+            Ok(decoded)
+        }
+        other => Err(StorageError::DecompressValue(format!(
+            "unknown compression marker byte {other}"
+        ))),
+    }
+}
+
+/// An opaque, serializable position within a scan of a [`StorageTableInner`] — the pk of the last
+/// row already emitted — produced by [`StorageTableInner::cursor_after`] and consumed by
+/// [`StorageTableInner::resume_from`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DurableCursor {
+    last_pk: Vec<u8>,
+}
+
+impl DurableCursor {
+    /// Serializes to a byte string a caller can write to any durable store; round-trips through
+    /// [`Self::deserialize`].
+    pub fn serialize(&self) -> Vec<u8> {
+        self.last_pk.clone()
+    }
+
+    /// Inverse of [`Self::serialize`].
+    pub fn deserialize(bytes: Vec<u8>) -> Self {
+        Self { last_pk: bytes }
+    }
+}
+
+/// A row scanned by [`StorageTableInner::sorted_by`], paired with its (already-extracted) sort
+/// column value so the bounded top-N heap can compare entries via `cmp_datum` without re-reading
+/// the column out of `row` on every comparison.
+struct SortedByEntry {
+    key: Datum,
+    order: OrderType,
+    row: OwnedRow,
+}
+
+impl PartialEq for SortedByEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == std::cmp::Ordering::Equal
+    }
+}
+
+impl Eq for SortedByEntry {}
+
+impl PartialOrd for SortedByEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SortedByEntry {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        cmp_datum(&self.key, &other.key, self.order)
+    }
+}
+
+/// Batch size for companion `visibility` lookups in [`StorageTableInner::scan_with_visibility`].
+const VISIBILITY_LOOKUP_BATCH_SIZE: usize = 1024;
+
+/// A row is visible unless `visibility_row` is absent or its column 0 isn't `true` — an absent
+/// row (no override present) and an explicit `false` are both treated as "hidden".
+fn is_row_visible(visibility_row: Option<&OwnedRow>) -> bool {
+    matches!(
+        visibility_row.and_then(|row| row.datum_at(0)),
+        Some(ScalarRefImpl::Bool(true))
+    )
+}
+
+/// Encodes `row` into a protobuf message described by `descriptor`, matching columns of `schema`
+/// to message fields by name.
+fn encode_row_to_proto(
+    schema: &Schema,
+    row: &OwnedRow,
+    descriptor: &prost_reflect::MessageDescriptor,
+) -> StorageResult<Vec<u8>> {
+    use prost_reflect::Value as PbValue;
+
+    let mut message = prost_reflect::DynamicMessage::new(descriptor.clone());
+    for (field, datum) in schema.fields().iter().zip(row.iter()) {
+        let Some(field_desc) = descriptor.get_field_by_name(&field.name) else {
+            return Err(StorageError::EncodeRow(format!(
+                "no field named `{}` in protobuf message `{}`",
+                field.name,
+                descriptor.full_name()
+            )));
+        };
+        let Some(scalar) = datum else {
+            // Leave NULLs as unset optional fields.
+            continue;
+        };
+        let value = match scalar {
+            ScalarRefImpl::Bool(v) => PbValue::Bool(v),
+            ScalarRefImpl::Int32(v) => PbValue::I32(v),
+            ScalarRefImpl::Int64(v) => PbValue::I64(v),
+            ScalarRefImpl::Float32(v) => PbValue::F32(v.into_inner()),
+            ScalarRefImpl::Float64(v) => PbValue::F64(v.into_inner()),
+            ScalarRefImpl::Utf8(v) => PbValue::String(v.to_owned()),
+            ScalarRefImpl::Bytea(v) => PbValue::Bytes(v.to_vec().into()),
+            other => {
+                return Err(StorageError::EncodeRow(format!(
+                    "column `{}` (value {:?}) has no protobuf encoding",
+                    field.name, other
+                )))
+            }
+        };
+        if !value.is_valid(&field_desc) {
+            return Err(StorageError::EncodeRow(format!(
+                "column `{}` value {:?} does not match the type of protobuf field `{}`",
+                field.name,
+                value,
+                field_desc.name()
+            )));
+        }
+        message.set_field(&field_desc, value);
+    }
+    Ok(message.encode_to_vec())
+}
+
+/// I/O counters for one scan, accumulated inside [`StorageTableInnerIterInner::into_stream`]'s
+/// reassembly loop as keys/values are consumed. `cells_read` counts the raw deserialized row's
+/// column count (before any output-column projection), matching what was actually read off
+/// storage rather than what the caller ultimately sees.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StorageTableIterStats {
+    pub rows_yielded: u64,
+    pub cells_read: u64,
+    pub bytes_read: u64,
+}
+
+/// A handle to a running scan's [`StorageTableIterStats`], obtained from
+/// [`StorageTableInner::batch_iter_with_stats`] or
+/// [`StorageTableInner::batch_iter_with_pk_bounds_and_stats`] alongside the
+/// [`StorageTableInnerIter`] itself. [`StorageTableInnerIter`] is an opaque `impl Trait` alias
+/// (not a nominal type), so a `stats()` method can't be added to the iterator directly -- this
+/// handle is a separate, cheaply cloneable value that stays live and up to date as the paired
+/// iterator is driven, the same way [`StorageTableInnerIterInner::row_deserializer`] is shared via
+/// `Arc` rather than owned per row.
+#[derive(Clone, Default)]
+pub struct StorageTableIterStatsHandle(Arc<Mutex<StorageTableIterStats>>);
+
+impl StorageTableIterStatsHandle {
+    /// A point-in-time copy of the counters; safe to call at any point during or after the scan.
+    pub fn snapshot(&self) -> StorageTableIterStats {
+        *self.0.lock()
+    }
+
+    fn record_read(&self, cells: u64, bytes: u64) {
+        let mut stats = self.0.lock();
+        stats.cells_read += cells;
+        stats.bytes_read += bytes;
+    }
+
+    fn record_yielded(&self) {
+        self.0.lock().rows_yielded += 1;
+    }
+}
+
+/// [`StorageTableInnerIterInner`] iterates on the storage table.
+struct StorageTableInnerIterInner<S: StateStore, SD: ValueRowSerde> {
+    /// An iterator that returns raw bytes from storage.
+    iter: S::IterStream,
+
+    mapping: Arc<ColumnMapping>,
+
+    row_deserializer: Arc<SD>,
+
+    /// Used for serializing and deserializing the primary key.
+    pk_serializer: Option<Arc<OrderedRowSerde>>,
+
+    output_indices: Vec<usize>,
+
+    /// the key part of output_indices.
+    key_output_indices: Option<Vec<usize>>,
+
+    /// the value part of output_indices.
+    value_output_indices: Vec<usize>,
+
+    /// used for deserializing key part of output row from pk.
+    output_row_in_key_indices: Vec<usize>,
+
+    /// When set, a row that fails to decode (bad cell count, pk that doesn't match the expected
+    /// boundary, ...) is logged and skipped instead of aborting the whole scan. Only
+    /// [`StorageTableInner::batch_iter_lenient`] and
+    /// [`StorageTableInner::batch_iter_with_pk_bounds_lenient`] set this; every other entrypoint
+    /// keeps the strict default of erroring out, since silently dropping rows is only acceptable
+    /// when the caller has explicitly opted in (e.g. an operator reading around corruption during
+    /// recovery).
+    lenient: bool,
+
+    /// Set only by [`StorageTableInner::batch_iter_with_stats`] and
+    /// [`StorageTableInner::batch_iter_with_pk_bounds_and_stats`]; see [`StorageTableIterStatsHandle`].
+    stats: Option<StorageTableIterStatsHandle>,
+
+    /// Set only by [`StorageTableInner::batch_iter_with_filter`] and
+    /// [`StorageTableInner::batch_iter_with_pk_bounds_and_filter`]. A row is dropped, not yielded,
+    /// when this returns `false` for it -- applied in [`Self::into_stream`] after reassembly (so it
+    /// sees the fully decoded, output-projected row) and before the row is ever handed to the
+    /// caller, keeping the residual predicate close to the storage layer instead of downstream in
+    /// an executor.
+    predicate: Option<Arc<RowPredicate>>,
+}
+
+/// The predicate type accepted by [`StorageTableInner::batch_iter_with_filter`] and
+/// [`StorageTableInner::batch_iter_with_pk_bounds_and_filter`].
+pub type RowPredicate = dyn Fn(&OwnedRow) -> bool + Send + Sync;
+
+impl<S: StateStore, SD: ValueRowSerde> StorageTableInnerIterInner<S, SD> {
     /// If `wait_epoch` is true, it will wait for the given epoch to be committed before iteration.
     #[allow(clippy::too_many_arguments)]
     async fn new(
@@ -688,6 +2225,9 @@ impl<S: StateStore, SD: ValueRowSerde> StorageTableInnerIterInner<S, SD> {
         raw_key_range: (Bound<Bytes>, Bound<Bytes>),
         read_options: ReadOptions,
         epoch: HummockReadEpoch,
+        lenient: bool,
+        stats: Option<StorageTableIterStatsHandle>,
+        predicate: Option<Arc<RowPredicate>>,
     ) -> StorageResult<Self> {
         let raw_epoch = epoch.get_epoch();
         store.try_wait_epoch(epoch).await?;
@@ -706,10 +2246,61 @@ impl<S: StateStore, SD: ValueRowSerde> StorageTableInnerIterInner<S, SD> {
             key_output_indices,
             value_output_indices,
             output_row_in_key_indices,
+            lenient,
+            stats,
+            predicate,
         };
         Ok(iter)
     }
 
+    /// Decode one storage row into its output projection. Pulled out of [`Self::into_stream`] so
+    /// the lenient path can catch a decode failure on one row without unwinding the whole stream.
+    fn decode_row(&self, key: &[u8], value: Bytes) -> StorageResult<OwnedRow> {
+        let full_row = self.row_deserializer.deserialize(&value)?;
+        if let Some(stats) = &self.stats {
+            stats.record_read(full_row.len() as u64, value.len() as u64);
+        }
+        let result_row_in_value = self
+            .mapping
+            .project(OwnedRow::new(full_row))
+            .into_owned_row();
+        match &self.key_output_indices {
+            Some(key_output_indices) => {
+                let result_row_in_key = match self.pk_serializer.clone() {
+                    Some(pk_serializer) => {
+                        let pk = pk_serializer.deserialize(key)?;
+
+                        pk.project(&self.output_row_in_key_indices).into_owned_row()
+                    }
+                    None => OwnedRow::empty(),
+                };
+
+                let mut result_row_vec = vec![];
+                for idx in &self.output_indices {
+                    if self.value_output_indices.contains(idx) {
+                        let item_position_in_value_indices = &self
+                            .value_output_indices
+                            .iter()
+                            .position(|p| idx == p)
+                            .unwrap();
+                        result_row_vec.push(
+                            result_row_in_value
+                                .index(*item_position_in_value_indices)
+                                .clone(),
+                        );
+                    } else {
+                        let item_position_in_pk_indices =
+                            key_output_indices.iter().position(|p| idx == p).unwrap();
+                        result_row_vec
+                            .push(result_row_in_key.index(item_position_in_pk_indices).clone());
+                    }
+                }
+                Ok(OwnedRow::new(result_row_vec))
+            }
+            None => Ok(result_row_in_value),
+        }
+    }
+
     /// Yield a row with its primary key.
     #[try_stream(ok = (Vec<u8>, OwnedRow), error = StorageError)]
     async fn into_stream(self) {
@@ -725,48 +2316,114 @@ impl<S: StateStore, SD: ValueRowSerde> StorageTableInnerIterInner<S, SD> {
         {
             let (_, key) = parse_raw_key_to_vnode_and_key(&raw_key);
 
-            let full_row = self.row_deserializer.deserialize(&value)?;
-            let result_row_in_value = self
-                .mapping
-                .project(OwnedRow::new(full_row))
-                .into_owned_row();
-            match &self.key_output_indices {
-                Some(key_output_indices) => {
-                    let result_row_in_key = match self.pk_serializer.clone() {
-                        Some(pk_serializer) => {
-                            let pk = pk_serializer.deserialize(key)?;
-
-                            pk.project(&self.output_row_in_key_indices).into_owned_row()
-                        }
-                        None => OwnedRow::empty(),
-                    };
-
-                    let mut result_row_vec = vec![];
-                    for idx in &self.output_indices {
-                        if self.value_output_indices.contains(idx) {
-                            let item_position_in_value_indices = &self
-                                .value_output_indices
-                                .iter()
-                                .position(|p| idx == p)
-                                .unwrap();
-                            result_row_vec.push(
-                                result_row_in_value
-                                    .index(*item_position_in_value_indices)
-                                    .clone(),
-                            );
-                        } else {
-                            let item_position_in_pk_indices =
-                                key_output_indices.iter().position(|p| idx == p).unwrap();
-                            result_row_vec
-                                .push(result_row_in_key.index(item_position_in_pk_indices).clone());
+            match self.decode_row(key, value) {
+                Ok(row) => {
+                    if let Some(predicate) = &self.predicate {
+                        if !predicate(&row) {
+                            continue;
                         }
                     }
-                    let row = OwnedRow::new(result_row_vec);
-
+                    if let Some(stats) = &self.stats {
+                        stats.record_yielded();
+                    }
                     yield (key.to_vec(), row)
                 }
-                None => yield (key.to_vec(), result_row_in_value),
+                Err(e) if self.lenient => {
+                    tracing::warn!(
+                        target: "events::storage::storage_table",
+                        pk = ?raw_key,
+                        error = %e,
+                        "skipping corrupt row in lenient scan"
+                    );
+                    continue;
+                }
+                Err(e) => return Err(e),
             }
         }
     }
 }
+
+/// A table whose columns are split across several keyspaces ("column families"), for wide tables
+/// that keep hot and cold columns in separate physical keyspaces while sharing one logical
+/// primary key. Reading a full row means reading the pk from every column family and merging the
+/// results; a pk missing from any column family is treated as the whole row not existing.
+pub struct MultiCfStorageTable<S: StateStore, SD: ValueRowSerde> {
+    /// One [`StorageTableInner`] per column family, each keyed by the same primary key.
+    column_families: Vec<StorageTableInner<S, SD>>,
+    /// For each output column, which column family holds it and that family's own output index
+    /// for the column.
+    column_to_cf: Vec<(usize, usize)>,
+}
+
+impl<S: StateStore, SD: ValueRowSerde> MultiCfStorageTable<S, SD> {
+    pub fn new(
+        column_families: Vec<StorageTableInner<S, SD>>,
+        column_to_cf: Vec<(usize, usize)>,
+    ) -> Self {
+        assert!(!column_families.is_empty());
+        Self {
+            column_families,
+            column_to_cf,
+        }
+    }
+
+    /// Reads a full row by point-getting the primary key from every column family and merging the
+    /// per-family rows according to `column_to_cf`. Returns `None` if any column family is
+    /// missing the pk, since a row can't be considered present with some of its columns absent.
+    pub async fn get_row(
+        &self,
+        pk: impl Row,
+        wait_epoch: HummockReadEpoch,
+    ) -> StorageResult<Option<OwnedRow>> {
+        let pk = pk.into_owned_row();
+        let mut cf_rows = Vec::with_capacity(self.column_families.len());
+        for cf in &self.column_families {
+            match cf.get_row(&pk, wait_epoch).await? {
+                Some(row) => cf_rows.push(row),
+                None => return Ok(None),
+            }
+        }
+
+        let merged = self
+            .column_to_cf
+            .iter()
+            .map(|&(cf_idx, cf_col_idx)| cf_rows[cf_idx].index(cf_col_idx).clone())
+            .collect_vec();
+        Ok(Some(OwnedRow::new(merged)))
+    }
+}
+
+/// A read view over a [`StorageTableInner`] that overlays a [`MemTable`]'s pending, un-flushed
+/// buffer on top of committed storage, so a transaction reading through it sees its own writes
+/// (read-your-writes) before they're committed. A pending delete masks the committed row; a
+/// pending put/update is returned instead of reading through to storage.
+pub struct TransactionalStorageTable<'a, S: StateStore, SD: ValueRowSerde> {
+    table: &'a StorageTableInner<S, SD>,
+    mem_table: &'a MemTable,
+}
+
+impl<'a, S: StateStore, SD: ValueRowSerde> TransactionalStorageTable<'a, S, SD> {
+    pub fn new(table: &'a StorageTableInner<S, SD>, mem_table: &'a MemTable) -> Self {
+        Self { table, mem_table }
+    }
+
+    /// Reads a row by pk, preferring the transaction's own pending write over committed storage.
+    pub async fn get_row(
+        &self,
+        pk: impl Row,
+        wait_epoch: HummockReadEpoch,
+    ) -> StorageResult<Option<OwnedRow>> {
+        let pk = pk.into_owned_row();
+        let vnode = self.table.compute_vnode_by_pk(&pk);
+        let serialized_pk =
+            serialize_pk_with_vnode(&pk, &self.table.pk_serializer, vnode);
+
+        match self.mem_table.get_key_op(&serialized_pk) {
+            Some(KeyOp::Insert(value)) | Some(KeyOp::Update((_, value))) => Ok(Some(
+                self.table.decode_row_from_value(&pk, value)?,
+            )),
+            Some(KeyOp::Delete(_)) => Ok(None),
+            None => self.table.get_row(pk, wait_epoch).await,
+        }
+    }
+}
@@ -84,6 +84,26 @@ impl Distribution {
 #[async_trait::async_trait]
 pub trait TableIter: Send {
     async fn next_row(&mut self) -> StorageResult<Option<OwnedRow>>;
+
+    /// Drives [`Self::next_row`] up to `n` times, collecting the rows into one `Vec` instead of
+    /// making the caller `.await` each row individually. Returns fewer than `n` rows (down to
+    /// empty) once the underlying stream is exhausted, never an error for reaching end of stream.
+    ///
+    /// This amortizes only the per-row `.await` overhead of driving the stream one item at a
+    /// time -- for [`StorageTableInner`](crate::table::batch_table::storage_table::StorageTableInner)'s
+    /// iterators specifically, row deserialization itself is already amortized independently of
+    /// this method: the `RowDeserializer` is built once per scan behind an `Arc` and shared by
+    /// every row the scan yields, not reallocated per row.
+    async fn next_row_batch(&mut self, n: usize) -> StorageResult<Vec<OwnedRow>> {
+        let mut rows = Vec::with_capacity(n);
+        for _ in 0..n {
+            match self.next_row().await? {
+                Some(row) => rows.push(row),
+                None => break,
+            }
+        }
+        Ok(rows)
+    }
 }
 
 pub async fn collect_data_chunk<E, S>(
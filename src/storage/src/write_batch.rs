@@ -65,6 +65,16 @@ impl<'a, S: StateStoreWrite> WriteBatch<'a, S> {
     }
 
     /// Delete all keys starting with `prefix`.
+    ///
+    /// This is the primitive a "drop everything under one prefix" operation -- e.g. dropping a
+    /// materialized view, which owns every key sharing its table id prefix -- wants: one range
+    /// tombstone here, applied at [`Self::ingest`] time, rather than an iterate-then-delete-each
+    /// loop over however many rows the view happens to have. There's no `Keyspace` type in this
+    /// crate to hang a `Keyspace::delete_prefix`/`MViewTable::drop_all` convenience off of (see
+    /// [`Self::delete_range`]'s doc comment on why the cell-based-storage-era owner of that prefix
+    /// no longer exists) -- a caller reaches this the same way
+    /// [`StorageTableInner`](crate::table::batch_table::storage_table::StorageTableInner)'s own
+    /// readers build their key ranges: by prefixing with the table's own serialized table id.
     pub fn delete_prefix(&mut self, prefix: impl AsRef<[u8]>) {
         let start_key = Bytes::from(prefix.as_ref().to_owned());
         let end_key = Bytes::from(next_key(&start_key));
@@ -73,6 +83,17 @@ impl<'a, S: StateStoreWrite> WriteBatch<'a, S> {
     }
 
     /// Delete all keys in this range.
+    ///
+    /// This is the same range-tombstone mechanism a bulk pk-range cleanup (e.g. TTL or partition
+    /// expiry on a materialized view) would want: the tombstone is recorded once here and applied
+    /// at [`Self::ingest`] time via [`StateStoreWrite::ingest_batch`]'s `delete_ranges`, rather
+    /// than generating one delete per row. There's no row-typed writer wrapper in this crate
+    /// (`ManagedMViewState`, the cell-based-storage-era owner of `put`/`delete`, no longer exists
+    /// -- see [`crate::table::batch_table::storage_table::compress_value_if_large`]'s doc comment)
+    /// to serialize a pk [`risingwave_common::row::Row`] bound into `start`/`end` for you; a caller
+    /// with a `Row` bound serializes it with the table's own pk serializer first, the same way
+    /// [`StorageTableInner`](crate::table::batch_table::storage_table::StorageTableInner)'s readers
+    /// do for their key ranges.
     pub fn delete_range(&mut self, start: Bound<impl AsRef<[u8]>>, end: Bound<impl AsRef<[u8]>>) {
         self.delete_ranges.push((
             start.map(|start| Bytes::from(start.as_ref().to_owned())),
@@ -129,11 +150,61 @@ impl<'a, S: StateStoreWrite> WriteBatch<'a, S> {
 
 #[cfg(test)]
 mod tests {
+    use std::ops::Bound;
+
     use bytes::Bytes;
+    use futures::TryStreamExt;
 
     use crate::memory::MemoryStateStore;
     use crate::storage_value::StorageValue;
-    use crate::store::{StateStoreWrite, WriteOptions};
+    use crate::store::{ReadOptions, StateStoreRead, StateStoreWrite, WriteOptions};
+
+    #[tokio::test]
+    async fn test_delete_range_removes_only_the_covered_keys() {
+        let state_store = MemoryStateStore::new();
+        let mut batch = state_store.start_write_batch(WriteOptions {
+            epoch: 1,
+            table_id: Default::default(),
+        });
+        for key in ["a", "b", "c", "d", "e"] {
+            batch.put(Bytes::from(key), StorageValue::new_put(key));
+        }
+        batch.ingest().await.unwrap();
+
+        // Delete the middle range "b" (inclusive) to "d" (exclusive), leaving "a", "d", "e" behind.
+        let mut batch = state_store.start_write_batch(WriteOptions {
+            epoch: 2,
+            table_id: Default::default(),
+        });
+        batch.delete_range(
+            Bound::Included(Bytes::from("b")),
+            Bound::Excluded(Bytes::from("d")),
+        );
+        batch.ingest().await.unwrap();
+
+        let remaining: Vec<_> = state_store
+            .iter(
+                (Bound::Unbounded, Bound::Unbounded),
+                2,
+                ReadOptions {
+                    table_id: Default::default(),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        let remaining_keys: Vec<_> = remaining
+            .iter()
+            .map(|(key, _)| key.user_key.table_key.0.clone())
+            .collect();
+        assert_eq!(
+            remaining_keys,
+            vec![Bytes::from("a"), Bytes::from("d"), Bytes::from("e")]
+        );
+    }
 
     #[tokio::test]
     async fn test_invalid_write_batch() {
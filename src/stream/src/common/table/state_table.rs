@@ -704,9 +704,30 @@ where
             .unwrap_or_else(|e| self.handle_mem_table_error(e));
     }
 
+    /// Panics in debug builds if `row` is narrower than what `pk_indices`/`value_indices` index
+    /// into, which would otherwise silently write a truncated row and surface as an "incomplete
+    /// item" error much later, at scan time.
+    fn debug_assert_row_width(&self, row: &impl Row) {
+        let max_index = self
+            .pk_indices
+            .iter()
+            .chain(self.value_indices.iter().flatten())
+            .copied()
+            .max();
+        if let Some(max_index) = max_index {
+            debug_assert!(
+                row.len() > max_index,
+                "row width {} does not match the table schema (expected at least {} columns)",
+                row.len(),
+                max_index + 1
+            );
+        }
+    }
+
     /// Insert a row into state table. Must provide a full row corresponding to the column desc of
     /// the table.
     pub fn insert(&mut self, value: impl Row) {
+        self.debug_assert_row_width(&value);
         let pk_indices = &self.pk_indices;
         let pk = (&value).project(pk_indices);
         if USE_WATERMARK_CACHE {
@@ -721,6 +742,7 @@ where
     /// Delete a row from state table. Must provide a full row of old value corresponding to the
     /// column desc of the table.
     pub fn delete(&mut self, old_value: impl Row) {
+        self.debug_assert_row_width(&old_value);
         let pk_indices = &self.pk_indices;
         let pk = (&old_value).project(pk_indices);
         if USE_WATERMARK_CACHE {